@@ -0,0 +1,137 @@
+/// Digits of 3x5 pixels, one string per row, '#' is a set pixel
+const DIGITS: [[&str; 5]; 10] = [
+    ["###", "# #", "# #", "# #", "###"],
+    [" # ", "## ", " # ", " # ", "###"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+];
+
+const COLON: [&str; 5] = [" ", "#", " ", "#", " "];
+
+const GLYPH_HEIGHT: usize = 5;
+
+/// Remaining time as shown in big digits: H:MM:SS, or MM:SS below an hour
+pub fn countdown_text(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs % 3600) / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// The pixels of the text with one empty column between the characters
+fn pixels(text: &str) -> Vec<Vec<bool>> {
+    let mut rows = vec![Vec::new(); GLYPH_HEIGHT];
+    for (i, c) in text.chars().enumerate() {
+        let glyph = match c.to_digit(10) {
+            Some(d) => DIGITS[d as usize],
+            None if c == ':' => COLON,
+            None => continue,
+        };
+        for (row, line) in rows.iter_mut().zip(glyph) {
+            if i > 0 {
+                row.push(false);
+            }
+            row.extend(line.chars().map(|p| p == '#'));
+        }
+    }
+    rows
+}
+
+/// Renders the text as large as it fits into the area: with full blocks, each pixel two cells
+/// wide so that it is about square, or with Braille dots if the area is too small for that.
+/// Returns no lines if the text doesn't fit at all.
+pub fn render(text: &str, width: u16, height: u16) -> Vec<String> {
+    let pixels = pixels(text);
+    let pixel_width = pixels[0].len();
+    let (width, height) = (width as usize, height as usize);
+
+    let scale = (1..=8)
+        .rev()
+        .find(|s| pixel_width * 2 * s <= width && GLYPH_HEIGHT * s <= height);
+    if let Some(scale) = scale {
+        return pixels
+            .iter()
+            .flat_map(|row| {
+                let line: String = row
+                    .iter()
+                    .map(|&set| if set { "█" } else { " " }.repeat(2 * scale))
+                    .collect();
+                std::iter::repeat_n(line, scale)
+            })
+            .collect();
+    }
+
+    // a Braille character holds 2x4 pixels
+    if pixel_width.div_ceil(2) > width || GLYPH_HEIGHT.div_ceil(4) > height {
+        return Vec::new();
+    }
+    const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+    (0..GLYPH_HEIGHT.div_ceil(4))
+        .map(|cell_row| {
+            (0..pixel_width.div_ceil(2))
+                .map(|cell_column| {
+                    let mut bits = 0;
+                    for (dy, dots) in DOTS.iter().enumerate() {
+                        for (dx, dot) in dots.iter().enumerate() {
+                            let set = pixels
+                                .get(cell_row * 4 + dy)
+                                .and_then(|row| row.get(cell_column * 2 + dx))
+                                .copied()
+                                .unwrap_or(false);
+                            if set {
+                                bits |= dot;
+                            }
+                        }
+                    }
+                    char::from_u32(0x2800 + bits).unwrap_or(' ')
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_hours_only_when_needed() {
+        assert_eq!(countdown_text(65), "01:05");
+        assert_eq!(countdown_text(3599), "59:59");
+        assert_eq!(countdown_text(3600 * 12 + 61), "12:01:01");
+    }
+
+    #[test]
+    fn scales_the_blocks_to_the_area() {
+        // 4 digits, a colon and 4 gaps are 17 pixels wide
+        let lines = render("00:05", 34, 5);
+        assert_eq!(lines.len(), 5);
+        assert!(lines.iter().all(|l| l.chars().count() == 34));
+        assert_eq!(lines[0], "██████  ██████      ██████  ██████");
+
+        let lines = render("00:05", 70, 11);
+        assert_eq!(lines.len(), 10);
+        assert!(lines.iter().all(|l| l.chars().count() == 68));
+    }
+
+    #[test]
+    fn falls_back_to_braille_in_small_areas() {
+        let lines = render("00:05", 20, 3);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.chars().count() == 9));
+        assert!(lines
+            .iter()
+            .flat_map(|l| l.chars())
+            .all(|c| ('\u{2800}'..='\u{28ff}').contains(&c)));
+
+        assert!(render("00:05", 8, 3).is_empty());
+        assert!(render("00:05", 20, 1).is_empty());
+    }
+}
@@ -1,7 +1,94 @@
-use std::{fmt, str::FromStr};
+use std::{env, fmt, str::FromStr, sync::OnceLock};
 
 use ratatui::style::Color;
 
+/// How many colors the terminal can show
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorSupport {
+    Basic,
+    Indexed,
+    TrueColor,
+}
+
+impl fmt::Display for ColorSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorSupport::Basic => write!(f, "16 colors"),
+            ColorSupport::Indexed => write!(f, "256 colors"),
+            ColorSupport::TrueColor => write!(f, "truecolor"),
+        }
+    }
+}
+
+/// Detects the color support from COLORTERM (truecolor or 24bit) and TERM (*256color*)
+pub fn color_support() -> ColorSupport {
+    static SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+    *SUPPORT.get_or_init(|| {
+        let colorterm = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        let term = env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Indexed
+        } else {
+            ColorSupport::Basic
+        }
+    })
+}
+
+/// The xterm default values of the 16 ANSI colors
+const ANSI_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// RGB value of a color of the 256-color palette: the 16 ANSI colors, a 6x6x6 cube and 24 grays
+pub fn index_to_rgb(index: u8) -> (u8, u8, u8) {
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => ANSI_RGB[index as usize],
+        16..=231 => {
+            let i = index - 16;
+            (
+                LEVELS[(i / 36) as usize],
+                LEVELS[(i / 6 % 6) as usize],
+                LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// The palette index in the range whose color is closest to the RGB value
+fn nearest_index(rgb: (u8, u8, u8), range: std::ops::Range<u16>) -> u8 {
+    let distance = |index: u16| {
+        let (r, g, b) = index_to_rgb(index as u8);
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, rgb.0) + d(g, rgb.1) + d(b, rgb.2)
+    };
+    range.min_by_key(|&i| distance(i)).unwrap_or(0) as u8
+}
+
+/// A configured color: one of the 16 named colors, a color of the 256-color palette (0-255)
+/// or a hex value (#rrggbb)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum AcceptedColors {
     Black,
     Red,
@@ -19,9 +106,70 @@ pub enum AcceptedColors {
     LightMagenta,
     LightCyan,
     White,
+    Indexed(u8),
+    Rgb(u8, u8, u8),
 }
 
 impl AcceptedColors {
+    /// The named colors in the order they are cycled through, which is also their palette index
+    pub fn all() -> Vec<AcceptedColors> {
+        let mut colors = vec![AcceptedColors::Black];
+        while colors.len() < 16 {
+            let next = colors[colors.len() - 1].next_color();
+            colors.push(next);
+        }
+        colors
+    }
+
+    /// Parses a color name (ignoring case), a palette index or a hex value. Unlike `from_str`
+    /// unknown colors are rejected.
+    pub fn parse(s: &str) -> Option<AcceptedColors> {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+            return Some(AcceptedColors::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+        if let Ok(index) = s.parse::<u8>() {
+            return Some(AcceptedColors::from_index(index));
+        }
+        AcceptedColors::all()
+            .into_iter()
+            .find(|color| color.to_string().eq_ignore_ascii_case(s))
+    }
+
+    /// The color at the index of the 256-color palette, the first 16 are the named colors
+    pub fn from_index(index: u8) -> AcceptedColors {
+        AcceptedColors::all()
+            .get(index as usize)
+            .copied()
+            .unwrap_or(AcceptedColors::Indexed(index))
+    }
+
+    /// The index of the color in the 256-color palette, hex values have none
+    pub fn palette_index(&self) -> Option<u8> {
+        match self {
+            AcceptedColors::Indexed(index) => Some(*index),
+            AcceptedColors::Rgb(..) => None,
+            named => AcceptedColors::all()
+                .iter()
+                .position(|color| color == named)
+                .map(|i| i as u8),
+        }
+    }
+
+    /// The accepted colors for messages, e.g. "Black, Red, Green, ..."
+    pub fn names() -> String {
+        let mut names: Vec<String> = AcceptedColors::all()
+            .iter()
+            .map(|color| color.to_string())
+            .collect();
+        names.push("a number from 0 to 255 or #rrggbb".to_string());
+        names.join(", ")
+    }
+
     pub fn next_color(&self) -> AcceptedColors {
         match self {
             AcceptedColors::Black => AcceptedColors::Red,
@@ -40,6 +188,8 @@ impl AcceptedColors {
             AcceptedColors::LightMagenta => AcceptedColors::LightCyan,
             AcceptedColors::LightCyan => AcceptedColors::White,
             AcceptedColors::White => AcceptedColors::Black,
+            AcceptedColors::Indexed(index) => AcceptedColors::from_index(index.wrapping_add(1)),
+            AcceptedColors::Rgb(..) => AcceptedColors::Black,
         }
     }
 
@@ -61,10 +211,36 @@ impl AcceptedColors {
             AcceptedColors::LightMagenta => AcceptedColors::LightBlue,
             AcceptedColors::LightCyan => AcceptedColors::LightMagenta,
             AcceptedColors::White => AcceptedColors::LightCyan,
+            AcceptedColors::Indexed(index) => AcceptedColors::from_index(index.wrapping_sub(1)),
+            AcceptedColors::Rgb(..) => AcceptedColors::White,
         }
     }
 
+    /// The color as the terminal can show it, see `color_support`
     pub fn to_color(&self) -> Color {
+        self.to_color_for(color_support())
+    }
+
+    /// The color downgraded to the nearest color the terminal can show
+    pub fn to_color_for(&self, support: ColorSupport) -> Color {
+        match (*self, support) {
+            (AcceptedColors::Indexed(index), ColorSupport::Basic) => {
+                AcceptedColors::from_index(nearest_index(index_to_rgb(index), 0..16))
+                    .to_color_for(support)
+            }
+            (AcceptedColors::Indexed(index), _) => Color::Indexed(index),
+            (AcceptedColors::Rgb(r, g, b), ColorSupport::TrueColor) => Color::Rgb(r, g, b),
+            (AcceptedColors::Rgb(r, g, b), ColorSupport::Indexed) => {
+                Color::Indexed(nearest_index((r, g, b), 16..256))
+            }
+            (AcceptedColors::Rgb(r, g, b), ColorSupport::Basic) => {
+                AcceptedColors::from_index(nearest_index((r, g, b), 0..16)).to_color_for(support)
+            }
+            (named, _) => named.named_color(),
+        }
+    }
+
+    fn named_color(&self) -> Color {
         match self {
             AcceptedColors::Black => Color::Black,
             AcceptedColors::Red => Color::Red,
@@ -82,6 +258,8 @@ impl AcceptedColors {
             AcceptedColors::LightMagenta => Color::LightMagenta,
             AcceptedColors::LightCyan => Color::LightCyan,
             AcceptedColors::White => Color::White,
+            AcceptedColors::Indexed(index) => Color::Indexed(*index),
+            AcceptedColors::Rgb(r, g, b) => Color::Rgb(*r, *g, *b),
         }
     }
 }
@@ -105,6 +283,8 @@ impl fmt::Display for AcceptedColors {
             AcceptedColors::LightMagenta => write!(f, "LightMagenta"),
             AcceptedColors::LightCyan => write!(f, "LightCyan"),
             AcceptedColors::White => write!(f, "White"),
+            AcceptedColors::Indexed(index) => write!(f, "{}", index),
+            AcceptedColors::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
         }
     }
 }
@@ -112,41 +292,108 @@ impl fmt::Display for AcceptedColors {
 impl FromStr for AcceptedColors {
     type Err = ();
 
+    /// Falls back to Green for unknown colors, use `parse` to validate them
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Black" => Ok(AcceptedColors::Black),
-            "Red" => Ok(AcceptedColors::Red),
-            "Green" => Ok(AcceptedColors::Green),
-            "Yellow" => Ok(AcceptedColors::Yellow),
-            "Blue" => Ok(AcceptedColors::Blue),
-            "Magenta" => Ok(AcceptedColors::Magenta),
-            "Cyan" => Ok(AcceptedColors::Cyan),
-            "Gray" => Ok(AcceptedColors::Gray),
-            "DarkGray" => Ok(AcceptedColors::DarkGray),
-            "LightRed" => Ok(AcceptedColors::LightRed),
-            "LightGreen" => Ok(AcceptedColors::LightGreen),
-            "LightYellow" => Ok(AcceptedColors::LightYellow),
-            "LightBlue" => Ok(AcceptedColors::LightBlue),
-            "LightMagenta" => Ok(AcceptedColors::LightMagenta),
-            "LightCyan" => Ok(AcceptedColors::LightCyan),
-            "White" => Ok(AcceptedColors::White),
-            _ => Ok(AcceptedColors::Green),
-        }
+        Ok(AcceptedColors::parse(s).unwrap_or(AcceptedColors::Green))
     }
 }
 
-pub fn get_background_color(darkmode: bool) -> Color {
-    if darkmode {
-        Color::Black
-    } else {
-        Color::White
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_ignoring_case() {
+        assert_eq!(
+            AcceptedColors::parse("lightblue"),
+            Some(AcceptedColors::LightBlue)
+        );
+        assert_eq!(AcceptedColors::parse(" Red "), Some(AcceptedColors::Red));
+        assert_eq!(AcceptedColors::parse("Purple"), None);
+    }
+
+    #[test]
+    fn parses_palette_indices() {
+        // the first 16 indices are the named colors
+        assert_eq!(AcceptedColors::parse("9"), Some(AcceptedColors::LightRed));
+        assert_eq!(
+            AcceptedColors::parse("208"),
+            Some(AcceptedColors::Indexed(208))
+        );
+        assert_eq!(AcceptedColors::parse("256"), None);
+        assert_eq!(AcceptedColors::parse("-1"), None);
+    }
+
+    #[test]
+    fn parses_hex_values() {
+        assert_eq!(
+            AcceptedColors::parse("#1a2B3c"),
+            Some(AcceptedColors::Rgb(0x1a, 0x2b, 0x3c))
+        );
+        assert_eq!(AcceptedColors::parse("#12345"), None);
+        assert_eq!(AcceptedColors::parse("#12345g"), None);
+        assert_eq!(AcceptedColors::Rgb(0x1a, 0x2b, 0x3c).to_string(), "#1a2b3c");
+    }
+
+    #[test]
+    fn unknown_colors_fall_back_to_green_only_in_from_str() {
+        assert_eq!(
+            AcceptedColors::from_str("Purple"),
+            Ok(AcceptedColors::Green)
+        );
+    }
+
+    #[test]
+    fn keeps_colors_the_terminal_can_show() {
+        let orange = AcceptedColors::Rgb(255, 135, 0);
+        assert_eq!(
+            orange.to_color_for(ColorSupport::TrueColor),
+            Color::Rgb(255, 135, 0)
+        );
+        assert_eq!(
+            AcceptedColors::Indexed(208).to_color_for(ColorSupport::Indexed),
+            Color::Indexed(208)
+        );
+        assert_eq!(
+            AcceptedColors::Cyan.to_color_for(ColorSupport::Basic),
+            Color::Cyan
+        );
+    }
+
+    #[test]
+    fn downgrades_hex_values_to_the_256_color_palette() {
+        assert_eq!(
+            AcceptedColors::Rgb(255, 135, 0).to_color_for(ColorSupport::Indexed),
+            Color::Indexed(208)
+        );
+        // grays use the gray ramp rather than the 16 colors
+        assert_eq!(
+            AcceptedColors::Rgb(128, 128, 128).to_color_for(ColorSupport::Indexed),
+            Color::Indexed(244)
+        );
+    }
+
+    #[test]
+    fn downgrades_to_the_16_colors() {
+        assert_eq!(
+            AcceptedColors::Rgb(250, 10, 10).to_color_for(ColorSupport::Basic),
+            Color::LightRed
+        );
+        assert_eq!(
+            AcceptedColors::Indexed(196).to_color_for(ColorSupport::Basic),
+            Color::LightRed
+        );
+        assert_eq!(
+            AcceptedColors::Indexed(232).to_color_for(ColorSupport::Basic),
+            Color::Black
+        );
     }
-}
 
-pub fn get_foreground_color(darkmode: bool) -> Color {
-    if darkmode {
-        Color::White
-    } else {
-        Color::Black
+    #[test]
+    fn maps_indices_to_rgb() {
+        assert_eq!(index_to_rgb(1), (205, 0, 0));
+        assert_eq!(index_to_rgb(16), (0, 0, 0));
+        assert_eq!(index_to_rgb(208), (255, 135, 0));
+        assert_eq!(index_to_rgb(255), (238, 238, 238));
     }
 }
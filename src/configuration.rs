@@ -1,17 +1,25 @@
-use chrono::Local;
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use ratatui::layout::Rect;
+use ratatui::style::Color;
 use ratatui::widgets::TableState;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::str::FromStr;
-use std::vec;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::str::FromStr;
 
 use crate::color::AcceptedColors;
+use crate::history::HistoryCache;
+use crate::keybindings::KeyMap;
+use crate::settings::{setting_index, SETTINGS};
+use crate::taskwarrior::TaskQueue;
+use crate::theme::{load_theme, Palette, Theme};
 use crate::timer::Timer;
-use crate::ui_states::{ConfigType, TimerAction, UiState};
-use crate::utils::{get_optional_timer_colors, reverse_bool};
+use crate::ui_states::{ColorPicker, InputMode, SortColumn, StatsRange, TypeEdit, UiState};
+use crate::utils::get_optional_timer_colors;
 
+/// Entries missing in config.json are taken from `new`, which has the defaults of `SETTINGS`
 #[derive(Serialize, Deserialize)]
+#[serde(default = "Configuration::new")]
 pub struct Configuration<'a> {
     pub darkmode: bool,
     pub activecolor: String,
@@ -21,6 +29,31 @@ pub struct Configuration<'a> {
     pub pomodoro_time: u64,
     pub pomodoro_smallbreak: u64,
     pub pomodoro_bigbreak: u64,
+    pub pomodoro_cycles: u64,
+    pub pomodoro_auto_continue: bool,
+    /// Pomodoros completed since the last long break
+    pub pomodoro_count: u64,
+    pub require_ack: bool,
+    pub snooze_minutes: u64,
+    pub complete_on_checklist: bool,
+    /// "Default" follows the darkmode setting
+    pub background_color: String,
+    pub foreground_color: String,
+    /// Name of a built-in theme or of a file in the themes directory
+    pub theme: String,
+    /// Shows the remaining time of the active timers in big digits above the columns
+    pub big_digits: bool,
+    /// Shows each column as a table with one row per timer instead of the timer boxes
+    pub table_view: bool,
+    /// Enters the focus mode when a timer of the type "focus" starts
+    pub auto_focus_mode: bool,
+    /// Keys of the actions per context that differ from the defaults, see `KeyMap::load`
+    pub keybindings: BTreeMap<String, BTreeMap<String, String>>,
+    pub adjust_step: u64,
+    pub mouse_support: bool,
+    pub taskwarrior_command: String,
+    pub taskwarrior_duration_uda: String,
+    pub taskwarrior_on_finish: String,
     pub timers: Vec<Timer>,
     pub timer_colors: HashMap<String, String>,
     #[serde(skip_serializing, skip_deserializing)]
@@ -33,81 +66,187 @@ pub struct Configuration<'a> {
     pub table_state_sets: TableState,
     #[serde(skip_serializing, skip_deserializing)]
     pub table_state_config: TableState,
+    /// Values of the Config tab as they are edited, one per entry of `SETTINGS`
+    #[serde(skip_serializing, skip_deserializing)]
+    pub setting_values: Vec<String>,
+    /// A value being typed for the selected setting
+    #[serde(skip_serializing, skip_deserializing)]
+    pub setting_edit: Option<String>,
+    /// The loaded theme, see `apply_theme`
+    #[serde(skip_serializing, skip_deserializing)]
+    pub theme_colors: Theme,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub input_mode: InputMode,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub selected_timer: Option<usize>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub tabs_area: Rect,
+    /// Rows of the Sets or Config table as they were drawn, with the index of their entry
+    #[serde(skip_serializing, skip_deserializing)]
+    pub table_rows: Vec<(usize, Rect)>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub timer_areas: Vec<(usize, Rect)>,
+    /// Id of the timer that is being dragged with the mouse
+    #[serde(skip_serializing, skip_deserializing)]
+    pub dragged_timer: Option<u16>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub column_offsets: [usize; 2],
+    #[serde(skip_serializing, skip_deserializing)]
+    pub column_page_sizes: [usize; 2],
+    /// Areas of the left and right column of the Timer tab, side by side or stacked
     #[serde(skip_serializing, skip_deserializing)]
-    pub darkmode_str: String,
+    pub column_areas: [Rect; 2],
     #[serde(skip_serializing, skip_deserializing)]
-    pub activecolor_str: String,
+    pub status_message: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
-    pub reverseadding_str: String,
+    pub stats_range: StatsRange,
     #[serde(skip_serializing, skip_deserializing)]
-    pub move_finished_timer_str: String,
+    pub stats_custom_range: Option<(NaiveDate, NaiveDate)>,
+    /// Taskwarrior commands of the timers that run in the background
     #[serde(skip_serializing, skip_deserializing)]
-    pub action_timeout_str: String,
+    pub task_queue: TaskQueue,
+    /// History shown in the Statistics tab
     #[serde(skip_serializing, skip_deserializing)]
-    pub pomodoro_time_table_str: String,
+    pub history: HistoryCache,
+    /// Tag of the `filter` command, only timers with it are shown
     #[serde(skip_serializing, skip_deserializing)]
-    pub pomodoro_smallbreak_table_str: String,
+    pub tag_filter: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
-    pub pomodoro_bigbreak_table_str: String,
+    pub stats_by_tag: bool,
+    /// Text of the `/` search in the Timer tab
     #[serde(skip_serializing, skip_deserializing)]
-    pub config_type: ConfigType,
+    pub search_query: String,
+    /// Whether the keys of the Config tab go to the Timer Types table
+    #[serde(skip_serializing, skip_deserializing)]
+    pub types_focus: bool,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub table_state_types: TableState,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub type_edit: Option<TypeEdit>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub color_picker: Option<ColorPicker>,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub table_sort: SortColumn,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub table_sort_descending: bool,
+    /// Shows only the running timer, without tabs and input line
+    #[serde(skip_serializing, skip_deserializing)]
+    pub focus_mode: bool,
+    /// The keys in effect, see `apply_keybindings`
+    #[serde(skip_serializing, skip_deserializing)]
+    pub key_map: KeyMap,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub help_scroll: u16,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub quit: bool,
+}
+
+impl<'a> Default for Configuration<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a> Configuration<'a> {
-    pub fn new(
-        pomodoro_time: u64,
-        pomodoro_smallbreak: u64,
-        pomodoro_bigbreak: u64,
-    ) -> Configuration<'a> {
-        Configuration {
-            pomodoro_time,
-            pomodoro_smallbreak,
-            pomodoro_bigbreak,
+    /// A configuration with the defaults of `SETTINGS`, the settings are left empty in the
+    /// literal and filled in from the registry
+    pub fn new() -> Configuration<'a> {
+        let mut config = Configuration {
+            darkmode: false,
+            activecolor: String::new(),
+            reverseadding: false,
+            move_finished_timer: false,
+            action_timeout: String::new(),
+            pomodoro_time: 0,
+            pomodoro_smallbreak: 0,
+            pomodoro_bigbreak: 0,
+            pomodoro_cycles: 0,
+            pomodoro_auto_continue: false,
+            pomodoro_count: 0,
+            require_ack: false,
+            snooze_minutes: 0,
+            complete_on_checklist: false,
+            background_color: String::new(),
+            foreground_color: String::new(),
+            theme: String::new(),
+            big_digits: false,
+            table_view: false,
+            auto_focus_mode: false,
+            keybindings: BTreeMap::new(),
+            adjust_step: 0,
+            mouse_support: false,
+            taskwarrior_command: String::new(),
+            taskwarrior_duration_uda: String::new(),
+            taskwarrior_on_finish: String::new(),
             timers: Vec::new(),
             timer_colors: get_optional_timer_colors(),
-            darkmode: true,
-            activecolor: "Green".to_string(),
-            reverseadding: false,
-            move_finished_timer: true,
-            action_timeout: "None".to_string(),
             show_popup: false,
             titles: Vec::new(),
             index: 0,
             table_state_sets: TableState::default(),
             table_state_config: TableState::default(),
-            darkmode_str: "".to_string(),
-            activecolor_str: "".to_string(),
-            reverseadding_str: "".to_string(),
-            move_finished_timer_str: "".to_string(),
-            action_timeout_str: "".to_string(),
-            pomodoro_time_table_str: "".to_string(),
-            pomodoro_smallbreak_table_str: "".to_string(),
-            pomodoro_bigbreak_table_str: "".to_string(),
-            config_type: ConfigType::default(),
+            setting_values: Vec::new(),
+            setting_edit: None,
+            theme_colors: Theme::default(),
+            input_mode: InputMode::default(),
+            selected_timer: None,
+            tabs_area: Rect::default(),
+            table_rows: Vec::new(),
+            timer_areas: Vec::new(),
+            dragged_timer: None,
+            column_offsets: [0, 0],
+            column_page_sizes: [1, 1],
+            status_message: None,
+            column_areas: [Rect::default(); 2],
+            stats_range: StatsRange::default(),
+            stats_custom_range: None,
+            task_queue: TaskQueue::default(),
+            history: HistoryCache::default(),
+            tag_filter: None,
+            stats_by_tag: false,
+            search_query: String::new(),
+            types_focus: false,
+            table_state_types: TableState::default(),
+            type_edit: None,
+            color_picker: None,
+            table_sort: SortColumn::default(),
+            table_sort_descending: false,
+            focus_mode: false,
+            key_map: KeyMap::default(),
+            help_scroll: 0,
+            quit: false,
+        };
+        for setting in &SETTINGS {
+            if let Ok(value) = setting.check(setting.default) {
+                (setting.set)(&mut config, value);
+            }
         }
+        config
     }
 
     pub fn write_config_to_file(&self) -> Result<(), std::io::Error> {
         std::fs::write("config.json", serde_json::to_string_pretty(self).unwrap())
     }
 
-    pub fn write_set_to_file(&self, set_name : String) -> Result<(), std::io::Error> {
+    pub fn write_set_to_file(&self, set_name: String) -> Result<(), std::io::Error> {
         let path = Path::new("sets");
 
         if !path.exists() {
             std::fs::create_dir_all(path)?;
         }
         let file_path = path.join(format!("{set_name}.json"));
-        std::fs::write(file_path,
-        serde_json::to_string_pretty(&self.timers).unwrap())
+        std::fs::write(
+            file_path,
+            serde_json::to_string_pretty(&self.timers).unwrap(),
+        )
     }
 
     pub fn read_set_files(&self) -> std::io::Result<Vec<String>> {
         let mut sets = Vec::new();
         let path = Path::new("sets");
-    
+
         let entries: std::fs::ReadDir = std::fs::read_dir(path)?;
-    
+
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
@@ -124,11 +263,14 @@ impl<'a> Configuration<'a> {
         let name = &items[index];
 
         let path = Path::new("sets");
-        let file_path = path.join(format!("{}", name));
+        let file_path = path.join(name);
         if file_path.exists() {
             std::fs::remove_file(file_path)
         } else {
-            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Set file not found"))
+            Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Set file not found",
+            ))
         }
     }
 
@@ -137,7 +279,7 @@ impl<'a> Configuration<'a> {
         let index = self.table_state_sets.selected().unwrap();
         let name = &items[index];
 
-        let path = Path::new("sets").join(format!("{}", name));
+        let path = Path::new("sets").join(name);
         let file = std::fs::File::open(&path)?;
         let reader = std::io::BufReader::new(file);
         let set: Vec<Timer> = serde_json::from_reader(reader)?;
@@ -156,13 +298,27 @@ impl<'a> Configuration<'a> {
         }
     }
 
+    /// First and last day of the range shown in the Statistics tab
+    pub fn stats_dates(&self) -> (NaiveDate, NaiveDate) {
+        let today = Local::now().date_naive();
+        match self.stats_range {
+            StatsRange::Today => (today, today),
+            StatsRange::ThisWeek => (
+                today - Duration::days(today.weekday().num_days_from_monday() as i64),
+                today,
+            ),
+            StatsRange::Last30Days => (today - Duration::days(29), today),
+            StatsRange::Custom => self.stats_custom_range.unwrap_or((today, today)),
+        }
+    }
+
     pub fn next_table_entry(&mut self) {
         let current_ui = UiState::get_current_ui(self.index);
         match current_ui {
             UiState::ConfigUi => {
                 let i = match self.table_state_config.selected() {
                     Some(i) => {
-                        if i >= 7 {
+                        if i >= SETTINGS.len() - 1 {
                             0
                         } else {
                             i + 1
@@ -171,7 +327,6 @@ impl<'a> Configuration<'a> {
                     None => 0,
                 };
                 self.table_state_config.select(Some(i));
-                self.config_type.next();
             }
             UiState::SetsUi => {
                 let i = match self.table_state_sets.selected() {
@@ -188,7 +343,6 @@ impl<'a> Configuration<'a> {
             }
             _ => {}
         }
-        
     }
 
     pub fn previous_table_entry(&mut self) {
@@ -198,7 +352,7 @@ impl<'a> Configuration<'a> {
                 let i = match self.table_state_config.selected() {
                     Some(i) => {
                         if i == 0 {
-                            7
+                            SETTINGS.len() - 1
                         } else {
                             i - 1
                         }
@@ -206,7 +360,6 @@ impl<'a> Configuration<'a> {
                     None => 0,
                 };
                 self.table_state_config.select(Some(i));
-                self.config_type.previous();
             }
             UiState::SetsUi => {
                 let i = match self.table_state_sets.selected() {
@@ -225,66 +378,131 @@ impl<'a> Configuration<'a> {
         }
     }
 
-    pub fn clear_table_entry(&mut self) {
-        match self.table_state_config.selected().unwrap() {
-            0 => self.darkmode_str.clear(),
-            1 => self.activecolor_str.clear(),
-            2 => self.reverseadding_str.clear(),
-            3 => self.move_finished_timer_str.clear(),
-            4 => self.action_timeout_str.clear(),
-            5 => self.pomodoro_time_table_str.clear(),
-            6 => self.pomodoro_smallbreak_table_str.clear(),
-            7 => self.pomodoro_bigbreak_table_str.clear(),
+    /// Selects a row of the table in the Sets or Config tab, e.g. after a mouse click
+    pub fn select_table_entry(&mut self, row: usize) {
+        match UiState::get_current_ui(self.index) {
+            UiState::ConfigUi if row < SETTINGS.len() => {
+                self.table_state_config.select(Some(row));
+            }
+            UiState::SetsUi if row < self.read_set_files().map(|s| s.len()).unwrap_or(0) => {
+                self.table_state_sets.select(Some(row));
+            }
             _ => {}
         }
     }
 
+    /// The values of the settings as they are stored, edits that weren't saved are lost
+    pub fn load_setting_values(&mut self) {
+        self.setting_values = SETTINGS.iter().map(|setting| (setting.get)(self)).collect();
+    }
+
+    /// Updates the Config tab after the setting was changed elsewhere, e.g. by a key of the
+    /// Timer tab
+    pub fn sync_setting(&mut self, name: &str) {
+        if let Some(i) = setting_index(name).filter(|i| *i < self.setting_values.len()) {
+            self.setting_values[i] = (SETTINGS[i].get)(self);
+        }
+    }
+
+    /// The index of the setting selected in the Config tab
+    pub fn selected_setting(&self) -> usize {
+        self.table_state_config.selected().unwrap_or(0)
+    }
+
+    /// Resets the selected setting to its default value, it is stored when saving
+    pub fn clear_table_entry(&mut self) {
+        let i = self.selected_setting();
+        self.setting_values[i] = SETTINGS[i].default.to_string();
+    }
+
+    /// Moves the selected setting to its next or previous value
+    pub fn cycle_setting(&mut self, forward: bool) {
+        let i = self.selected_setting();
+        self.setting_values[i] = SETTINGS[i].cycle(&self.setting_values[i], forward);
+    }
+
+    /// Stores the values of the Config tab and writes the config file. Values that aren't
+    /// valid are put back to the stored ones, the first of them is reported.
     pub fn save_table_changes(&mut self) {
-        self.darkmode = if self.darkmode_str.is_empty() {
-            self.darkmode_str = "false".to_string();
-            false
-        } else {
-            self.darkmode_str.parse::<bool>().unwrap_or_default()
-        };
-        self.activecolor = if self.activecolor_str.is_empty() {
-            self.activecolor_str = "Green".to_string();
-            "Green".to_string()
-        } else {
-            self.activecolor_str.clone()
-        };
+        if self.setting_values.len() != SETTINGS.len() {
+            self.load_setting_values();
+        }
+        let mut errors = Vec::new();
+        for (i, setting) in SETTINGS.iter().enumerate() {
+            match setting.check(&self.setting_values[i]) {
+                Ok(value) => {
+                    self.setting_values[i] = value.to_string();
+                    (setting.set)(self, value);
+                }
+                Err(e) => {
+                    self.setting_values[i] = (setting.get)(self);
+                    errors.push(e);
+                }
+            }
+        }
+        self.status_message = errors.into_iter().next();
+        if let Err(e) = self.apply_theme() {
+            self.status_message = Some(e);
+        }
+        if let Err(e) = self.write_config_to_file() {
+            self.status_message = Some(format!("Could not write config.json: {}", e));
+        }
+    }
 
-        self.reverseadding = self.reverseadding_str.parse::<bool>().unwrap_or_default();
-        self.move_finished_timer = self
-            .move_finished_timer_str
-            .parse::<bool>()
-            .unwrap_or_default();
+    /// Loads the keybindings of the config file, falling back to the default keys if they
+    /// can't be used
+    pub fn apply_keybindings(&mut self) -> Result<(), String> {
+        match KeyMap::load(&self.keybindings) {
+            Ok(key_map) => {
+                self.key_map = key_map;
+                Ok(())
+            }
+            Err(e) => {
+                self.key_map = KeyMap::default();
+                Err(format!("{}, using the default keys", e))
+            }
+        }
+    }
 
-        self.action_timeout = if self.action_timeout_str.is_empty() {
-            self.action_timeout_str = "None".to_string();
-            "None".to_string()
-        } else {
-            self.action_timeout_str.clone()
-        };
-        self.pomodoro_time = if self.pomodoro_time_table_str.is_empty() {
-            self.pomodoro_time_table_str = "25".to_string();
-            25
-        } else {
-            self.pomodoro_time_table_str.parse::<u64>().unwrap()
-        };
-        self.pomodoro_smallbreak = if self.pomodoro_smallbreak_table_str.is_empty() {
-            self.pomodoro_smallbreak_table_str = "5".to_string();
-            5
-        } else {
-            self.pomodoro_smallbreak_table_str.parse::<u64>().unwrap()
-        };
+    /// Loads the configured theme, falling back to the default theme if it can't be read
+    pub fn apply_theme(&mut self) -> Result<(), String> {
+        match load_theme(&self.theme, self.darkmode) {
+            Ok(theme) => {
+                self.theme_colors = theme;
+                Ok(())
+            }
+            Err(e) => {
+                self.theme_colors = load_theme("Default", self.darkmode)?;
+                Err(format!("Theme '{}' can't be loaded: {}", self.theme, e))
+            }
+        }
+    }
 
-        self.pomodoro_bigbreak = if self.pomodoro_bigbreak_table_str.is_empty() {
-            self.pomodoro_bigbreak_table_str = "10".to_string();
-            10
-        } else {
-            self.pomodoro_bigbreak_table_str.parse::<u64>().unwrap()
-        };
-        self.write_config_to_file().unwrap();
+    /// The colors of the theme with the background and foreground settings applied
+    pub fn palette(&self) -> Palette {
+        let mut palette = self.theme_colors.palette(self.active_color());
+        if let Some(color) = AcceptedColors::parse(&self.background_color) {
+            palette.background = color.to_color();
+        }
+        if let Some(color) = AcceptedColors::parse(&self.foreground_color) {
+            palette.foreground = color.to_color();
+        }
+        palette
+    }
+
+    /// Color of active timers, headers and highlights
+    pub fn active_color(&self) -> Color {
+        AcceptedColors::from_str(&self.activecolor)
+            .unwrap()
+            .to_color()
+    }
+
+    pub fn background(&self) -> Color {
+        self.palette().background
+    }
+
+    pub fn foreground(&self) -> Color {
+        self.palette().foreground
     }
 
     pub fn update_timers(&mut self) {
@@ -304,7 +522,11 @@ impl<'a> Configuration<'a> {
                     last_right = Some(i);
                 }
             }
-            timer.id = i as u16;
+            if timer.id != i as u16 {
+                // the id of a dragged timer would name another timer now
+                self.dragged_timer = None;
+                timer.id = i as u16;
+            }
             timer.is_active = false;
             timer.action_info = "   ".to_string();
         }
@@ -369,6 +591,261 @@ impl<'a> Configuration<'a> {
         self.timers.iter().filter(|t| !t.left_view).count()
     }
 
+    /// Indices of the first unfinished or unacknowledged timer of each column, i.e. the timers
+    /// that are running or hold their column until the user moves on
+    pub fn running_timer_indices(&self) -> Vec<usize> {
+        [true, false]
+            .iter()
+            .filter_map(|&left_view| {
+                self.timers.iter().position(|t| {
+                    t.left_view == left_view && (t.timeleft_secs != 0 || t.awaiting_ack)
+                })
+            })
+            .collect()
+    }
+
+    /// Indices of all timers in the left or right column, in display order
+    pub fn column_indices(&self, left_view: bool) -> Vec<usize> {
+        self.timers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.left_view == left_view)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Whether the timer passes the `filter` and the search of the Timer tab
+    pub fn is_visible(&self, timer: &Timer) -> bool {
+        self.tag_filter
+            .as_ref()
+            .is_none_or(|tag| timer.tags.contains(tag))
+            && self.matches_search(timer)
+    }
+
+    /// Whether the description, type or a tag contains the search text (ignoring case) or the
+    /// search is the id of the timer, e.g. "3" or "@3"
+    pub fn matches_search(&self, timer: &Timer) -> bool {
+        let query = self.search_query.to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        query.trim_start_matches('@') == timer.id.to_string()
+            || timer.description.to_lowercase().contains(&query)
+            || timer
+                .timer_type
+                .as_ref()
+                .is_some_and(|t| t.to_lowercase().contains(&query))
+            || timer
+                .tags
+                .iter()
+                .any(|tag| format!("#{}", tag.to_lowercase()).contains(&query))
+    }
+
+    /// Visible timers of both columns in the order in which n/N go through them
+    fn visible_timers(&self) -> Vec<usize> {
+        let mut timers = self.display_column_indices(true);
+        timers.extend(self.display_column_indices(false));
+        timers
+    }
+
+    /// Moves the selection to the first visible timer if the selected one is filtered out
+    pub fn select_first_visible(&mut self) {
+        let selected_visible = self
+            .selected_timer
+            .and_then(|i| self.timers.get(i))
+            .is_some_and(|t| self.is_visible(t));
+        if !selected_visible {
+            self.selected_timer = self.visible_timers().first().copied();
+        }
+    }
+
+    /// Selects the next or previous timer matching the search, wrapping around at the ends
+    pub fn select_match(&mut self, forward: bool) {
+        let matches = self.visible_timers();
+        if matches.is_empty() {
+            return;
+        }
+        let next = match self
+            .selected_timer
+            .and_then(|s| matches.iter().position(|&i| i == s))
+        {
+            Some(pos) if forward => (pos + 1) % matches.len(),
+            Some(pos) => (pos + matches.len() - 1) % matches.len(),
+            None => 0,
+        };
+        self.selected_timer = Some(matches[next]);
+    }
+
+    /// Indices of the timers of a column that are shown with the current filter
+    pub fn visible_column_indices(&self, left_view: bool) -> Vec<usize> {
+        self.column_indices(left_view)
+            .into_iter()
+            .filter(|&i| self.is_visible(&self.timers[i]))
+            .collect()
+    }
+
+    /// Visible timers of a column in the order they are shown: sorted by the selected column
+    /// in the table view, otherwise in queue order
+    pub fn display_column_indices(&self, left_view: bool) -> Vec<usize> {
+        let mut column = self.visible_column_indices(left_view);
+        if !self.table_view || self.table_sort == SortColumn::Queue {
+            return column;
+        }
+        column.sort_by(|&a, &b| {
+            let (a, b) = (&self.timers[a], &self.timers[b]);
+            let ordering = match self.table_sort {
+                SortColumn::Queue | SortColumn::Id => a.id.cmp(&b.id),
+                SortColumn::Remaining => a.timeleft_secs.cmp(&b.timeleft_secs),
+                // finished timers have no end time in the queue, they go first
+                SortColumn::EndTime => {
+                    (a.timeleft_secs != 0, a.endtime).cmp(&(b.timeleft_secs != 0, b.endtime))
+                }
+                SortColumn::Type => a.timer_type.cmp(&b.timer_type),
+                SortColumn::Description => a
+                    .description
+                    .to_lowercase()
+                    .cmp(&b.description.to_lowercase()),
+                SortColumn::Repeats => a.repeat_times.cmp(&b.repeat_times),
+            };
+            if self.table_sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        column
+    }
+
+    /// Whether the order on screen differs from the queue, so timers can't be moved by position
+    pub fn is_sorted_view(&self) -> bool {
+        self.table_view && self.table_sort != SortColumn::Queue
+    }
+
+    /// Keeps the selection on an existing timer after timers were removed or moved
+    pub fn clamp_selection(&mut self) {
+        if self.timers.is_empty() {
+            self.selected_timer = None;
+        } else if let Some(i) = self.selected_timer {
+            if i >= self.timers.len() {
+                self.selected_timer = Some(self.timers.len() - 1);
+            }
+        }
+    }
+
+    fn select_in_column(&mut self, step: isize) {
+        let Some(selected) = self.selected_timer else {
+            self.selected_timer = if self.timers.is_empty() {
+                None
+            } else {
+                Some(0)
+            };
+            return;
+        };
+        let column = self.display_column_indices(self.timers[selected].left_view);
+        if column.is_empty() {
+            return;
+        }
+        let pos = column.iter().position(|&i| i == selected).unwrap_or(0) as isize;
+        let new_pos = (pos + step).clamp(0, column.len() as isize - 1);
+        self.selected_timer = Some(column[new_pos as usize]);
+    }
+
+    pub fn select_next_timer(&mut self) {
+        self.select_in_column(1);
+    }
+
+    pub fn select_previous_timer(&mut self) {
+        self.select_in_column(-1);
+    }
+
+    /// Moves the selection by the number of timers visible in the column
+    pub fn select_page(&mut self, down: bool) {
+        let left_view = self
+            .selected_timer
+            .and_then(|i| self.timers.get(i))
+            .is_none_or(|t| t.left_view);
+        let page = self.column_page_sizes[if left_view { 0 } else { 1 }] as isize;
+        self.select_in_column(if down { page } else { -page });
+    }
+
+    /// Moves the selection to the timer at the same height in the other column
+    pub fn select_other_column(&mut self) {
+        let Some(selected) = self.selected_timer else {
+            self.select_next_timer();
+            return;
+        };
+        let left_view = self.timers[selected].left_view;
+        let column = self.display_column_indices(left_view);
+        let other_column = self.display_column_indices(!left_view);
+        if other_column.is_empty() {
+            return;
+        }
+        let pos = column.iter().position(|&i| i == selected).unwrap_or(0);
+        self.selected_timer = Some(other_column[pos.min(other_column.len() - 1)]);
+    }
+
+    /// Swaps the selected timer with its neighbour in the same column
+    pub fn move_selected_timer(&mut self, up: bool) {
+        let Some(selected) = self.selected_timer else {
+            return;
+        };
+        let column = self.visible_column_indices(self.timers[selected].left_view);
+        let pos = column.iter().position(|&i| i == selected).unwrap_or(0);
+        let neighbour = if up {
+            pos.checked_sub(1).map(|p| column[p])
+        } else {
+            column.get(pos + 1).copied()
+        };
+        if let Some(neighbour) = neighbour {
+            self.timers.swap(selected, neighbour);
+            self.selected_timer = Some(neighbour);
+        }
+    }
+
+    /// Moves a timer to the position of `target` (or the end of the column if there is none)
+    /// and into the given column
+    pub fn drop_timer(&mut self, from: usize, target: Option<usize>, left_view: bool) {
+        if from >= self.timers.len() || target == Some(from) {
+            return;
+        }
+        let mut timer = self.timers.remove(from);
+        // a timer dropped into the other column is inserted before the target timer
+        let to = match target {
+            Some(target) if target > from && timer.left_view != left_view => target - 1,
+            Some(target) => target,
+            None => self.timers.len(),
+        }
+        .min(self.timers.len());
+        timer.left_view = left_view;
+        self.timers.insert(to, timer);
+        self.selected_timer = Some(to);
+    }
+
+    /// Ends the notify-send processes of all timers, e.g. before the app quits
+    pub fn close_notifications(&mut self) {
+        for timer in &mut self.timers {
+            timer.close_notification();
+        }
+    }
+
+    /// Ids of the marked timers that the filter and search show, or of the selected timer if
+    /// none of them are marked
+    pub fn target_timer_ids(&self) -> Vec<u16> {
+        let marked: Vec<u16> = self
+            .timers
+            .iter()
+            .filter(|t| t.marked && self.is_visible(t))
+            .map(|t| t.id)
+            .collect();
+        if !marked.is_empty() {
+            return marked;
+        }
+        self.selected_timer
+            .and_then(|i| self.timers.get(i))
+            .map(|t| vec![t.id])
+            .unwrap_or_default()
+    }
+
     pub fn check_all_timers_done(&mut self) -> bool {
         for timer in self.timers.iter() {
             if timer.timeleft_secs > 0 {
@@ -377,94 +854,113 @@ impl<'a> Configuration<'a> {
         }
         true
     }
+}
 
-    pub fn move_value_right(&mut self) {
-        match self.config_type {
-            ConfigType::DarkMode => self.darkmode_str = reverse_bool(&self.darkmode_str),
-            ConfigType::ActiveColor => {
-                let parsed_color = AcceptedColors::from_str(&self.activecolor_str)
-                    .unwrap()
-                    .next_color();
-                self.activecolor_str = parsed_color.to_string();
-            }
-            ConfigType::ReverseAddingTimer => {
-                self.reverseadding_str = reverse_bool(&self.reverseadding_str)
-            }
-            ConfigType::MoveFinishedTimer => {
-                self.move_finished_timer_str = reverse_bool(&self.move_finished_timer_str)
-            }
-            ConfigType::ActionAfterTimer => {
-                let parsed_value = TimerAction::from_str(&self.action_timeout_str)
-                    .unwrap()
-                    .next();
-                self.action_timeout_str = parsed_value.to_string();
-            }
-            ConfigType::PomodoroTime => {
-                let mut parsed_value = self.pomodoro_time_table_str.parse::<i32>().unwrap();
-                if parsed_value < 99 {
-                    parsed_value += 1;
-                }
-                self.pomodoro_time_table_str = parsed_value.to_string();
-            }
-            ConfigType::PomodoroSmallBreak => {
-                let mut parsed_value = self.pomodoro_smallbreak_table_str.parse::<i32>().unwrap();
-                if parsed_value < 99 {
-                    parsed_value += 1;
-                }
-                self.pomodoro_smallbreak_table_str = parsed_value.to_string();
-            }
-            ConfigType::PomodoroBigBreak => {
-                let mut parsed_value = self.pomodoro_bigbreak_table_str.parse::<i32>().unwrap();
-                if parsed_value < 99 {
-                    parsed_value += 1;
-                }
-                self.pomodoro_bigbreak_table_str = parsed_value.to_string();
-            }
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with(descriptions: &[&str]) -> Configuration<'static> {
+        let mut config = Configuration::new();
+        for description in descriptions {
+            config
+                .timers
+                .push(Timer::new(description.to_string(), 60, true, None));
+        }
+        config.update_timers();
+        config
     }
 
-    pub fn move_value_left(&mut self) {
-        match self.config_type {
-            ConfigType::DarkMode => self.darkmode_str = reverse_bool(&self.darkmode_str),
-            ConfigType::ActiveColor => {
-                let parsed_color = AcceptedColors::from_str(&self.activecolor_str)
-                    .unwrap()
-                    .previous_color();
-                self.activecolor_str = parsed_color.to_string();
-            }
-            ConfigType::ReverseAddingTimer => {
-                self.reverseadding_str = reverse_bool(&self.reverseadding_str)
-            }
-            ConfigType::MoveFinishedTimer => {
-                self.move_finished_timer_str = reverse_bool(&self.move_finished_timer_str)
-            }
-            ConfigType::ActionAfterTimer => {
-                let parsed_value = TimerAction::from_str(&self.action_timeout_str)
-                    .unwrap()
-                    .previous();
-                self.action_timeout_str = parsed_value.to_string();
-            }
-            ConfigType::PomodoroTime => {
-                let mut parsed_value = self.pomodoro_time_table_str.parse::<i32>().unwrap();
-                if parsed_value > 0 {
-                    parsed_value -= 1;
-                }
-                self.pomodoro_time_table_str = parsed_value.to_string();
-            }
-            ConfigType::PomodoroSmallBreak => {
-                let mut parsed_value = self.pomodoro_smallbreak_table_str.parse::<i32>().unwrap();
-                if parsed_value > 0 {
-                    parsed_value -= 1;
-                }
-                self.pomodoro_smallbreak_table_str = parsed_value.to_string();
-            }
-            ConfigType::PomodoroBigBreak => {
-                let mut parsed_value = self.pomodoro_bigbreak_table_str.parse::<i32>().unwrap();
-                if parsed_value > 0 {
-                    parsed_value -= 1;
-                }
-                self.pomodoro_bigbreak_table_str = parsed_value.to_string();
-            }
-        };
+    fn matching(config: &Configuration) -> Vec<u16> {
+        config
+            .timers
+            .iter()
+            .filter(|t| config.matches_search(t))
+            .map(|t| t.id)
+            .collect()
+    }
+
+    #[test]
+    fn searches_ids_descriptions_types_and_tags() {
+        let mut config = config_with(&["Read", "Write 3 pages", "Call", "Review", "Mail"]);
+        config.timers[2].timer_type = Some("Focus".to_string());
+        config.timers[4].tags = vec!["clientA".to_string()];
+        assert_eq!(matching(&config), [0, 1, 2, 3, 4]);
+
+        config.search_query = "3".to_string();
+        assert_eq!(matching(&config), [1, 3]);
+        config.search_query = "@3".to_string();
+        assert_eq!(matching(&config), [3]);
+        config.search_query = "REV".to_string();
+        assert_eq!(matching(&config), [3]);
+        config.search_query = "focus".to_string();
+        assert_eq!(matching(&config), [2]);
+        config.search_query = "#client".to_string();
+        assert_eq!(matching(&config), [4]);
+    }
+
+    #[test]
+    fn selects_the_matches_in_turn() {
+        let mut config = config_with(&["Read", "Write", "Review"]);
+        config.timers[1].left_view = false;
+        config.search_query = "r".to_string();
+        config.selected_timer = Some(2);
+        // the left column comes first, then the right one
+        config.select_match(true);
+        assert_eq!(config.selected_timer, Some(1));
+        config.select_match(true);
+        assert_eq!(config.selected_timer, Some(0));
+        config.select_match(false);
+        assert_eq!(config.selected_timer, Some(1));
+
+        config.search_query = "rev".to_string();
+        config.select_first_visible();
+        assert_eq!(config.selected_timer, Some(2));
+    }
+
+    #[test]
+    fn acts_only_on_the_marked_timers_that_are_shown() {
+        let mut config = config_with(&["Read", "Write", "Review"]);
+        config.selected_timer = Some(1);
+        assert_eq!(config.target_timer_ids(), [1]);
+
+        config.timers[0].marked = true;
+        config.timers[2].marked = true;
+        assert_eq!(config.target_timer_ids(), [0, 2]);
+        config.search_query = "rev".to_string();
+        assert_eq!(config.target_timer_ids(), [2]);
+    }
+
+    #[test]
+    fn falls_back_to_the_default_theme() {
+        let mut config = config_with(&[]);
+        config.darkmode = false;
+        config.theme = "solarized".to_string();
+        assert!(config.apply_theme().is_ok());
+        assert_eq!(config.theme_colors.background, "#002b36");
+
+        config.theme = "no-such-theme".to_string();
+        let error = config.apply_theme().err().unwrap();
+        assert!(error.starts_with("Theme 'no-such-theme' can't be loaded: "));
+        assert_eq!(config.theme_colors.background, "White");
+    }
+
+    #[test]
+    fn sorts_the_columns_only_in_the_table_view() {
+        let mut config = config_with(&["write", "Call", "read"]);
+        config.table_sort = SortColumn::Description;
+        assert_eq!(config.display_column_indices(true), [0, 1, 2]);
+        assert!(!config.is_sorted_view());
+
+        config.table_view = true;
+        assert_eq!(config.display_column_indices(true), [1, 2, 0]);
+        assert!(config.is_sorted_view());
+        config.table_sort_descending = true;
+        assert_eq!(config.display_column_indices(true), [0, 2, 1]);
+
+        // moving through the column follows the order on screen
+        config.selected_timer = Some(0);
+        config.select_next_timer();
+        assert_eq!(config.selected_timer, Some(2));
     }
 }
@@ -0,0 +1,346 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::str::FromStr;
+
+use crate::configuration::Configuration;
+use crate::history::{read_history, HistoryEntry, Outcome};
+
+pub enum ExportFormat {
+    Csv,
+    Ics,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(ExportFormat::Csv),
+            "ics" | "ical" => Ok(ExportFormat::Ics),
+            _ => Err(format!("unknown export format '{}', use csv or ics", s)),
+        }
+    }
+}
+
+/// A tracked or planned period of time that is written as one CSV row or calendar event
+pub struct ExportEntry {
+    pub description: String,
+    pub timer_type: Option<String>,
+    pub tags: Vec<String>,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub duration_secs: u64,
+}
+
+impl ExportEntry {
+    /// History entries that never started have no tracked time and are left out. The duration
+    /// is the time the timer counted down, not the time between start and end.
+    fn from_history(entry: &HistoryEntry) -> Option<Self> {
+        Some(Self {
+            description: entry.description.clone(),
+            timer_type: entry.timer_type.clone(),
+            tags: entry.tags.clone(),
+            start: entry.started_at?,
+            end: entry.ended_at,
+            duration_secs: entry.focused_secs(),
+        })
+    }
+}
+
+/// Options of the `export` command: `export <csv|ics> [--from DATE] [--to DATE] [--queue]
+/// [--output FILE]`
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub queue: bool,
+    pub output: Option<String>,
+}
+
+impl ExportOptions {
+    pub fn parse(args: &[&str]) -> Result<Self, String> {
+        let mut args = args.iter();
+        let format = args
+            .next()
+            .ok_or("missing export format, use csv or ics")?
+            .parse()?;
+        let mut options = Self {
+            format,
+            from: None,
+            to: None,
+            queue: false,
+            output: None,
+        };
+        while let Some(arg) = args.next() {
+            match *arg {
+                "--from" | "--to" => {
+                    let value = args.next().ok_or(format!("missing date after {}", arg))?;
+                    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .map_err(|_| format!("invalid date '{}', use YYYY-MM-DD", value))?;
+                    if *arg == "--from" {
+                        options.from = Some(date);
+                    } else {
+                        options.to = Some(date);
+                    }
+                }
+                "--queue" => options.queue = true,
+                "--output" | "-o" => {
+                    options.output = Some(
+                        args.next()
+                            .ok_or("missing file after --output")?
+                            .to_string(),
+                    )
+                }
+                _ => return Err(format!("unknown export option '{}'", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    fn default_output(&self) -> String {
+        let name = if self.queue { "queue" } else { "export" };
+        match self.format {
+            ExportFormat::Csv => format!("{}.csv", name),
+            ExportFormat::Ics => format!("{}.ics", name),
+        }
+    }
+}
+
+/// Writes the completed timers (or with `--queue` the planned timers) to a file and
+/// returns its path
+pub fn run_export(args: &[&str], config: &Configuration) -> Result<String, String> {
+    let options = ExportOptions::parse(args)?;
+    let entries = if options.queue {
+        queue_entries(config)
+    } else {
+        let history = read_history().map_err(|e| e.to_string())?;
+        history_entries(&history, options.from, options.to)
+    };
+    let content = match options.format {
+        ExportFormat::Csv => to_csv(&entries),
+        ExportFormat::Ics => to_ics(&entries),
+    };
+    let output = options.output.clone().unwrap_or(options.default_output());
+    std::fs::write(&output, content).map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+/// The completed timers that ended in the date range, skipped, removed and interrupted timers
+/// are left out
+pub fn history_entries(
+    history: &[HistoryEntry],
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<ExportEntry> {
+    history
+        .iter()
+        .filter(|e| e.outcome == Outcome::Completed)
+        .filter(|e| {
+            let day = e.ended_at.date_naive();
+            from.is_none_or(|from| day >= from) && to.is_none_or(|to| day <= to)
+        })
+        .filter_map(ExportEntry::from_history)
+        .collect()
+}
+
+/// The unfinished timers with their projected start and end times
+pub fn queue_entries(config: &Configuration) -> Vec<ExportEntry> {
+    config
+        .timers
+        .iter()
+        .filter(|t| t.timeleft_secs > 0)
+        .map(|t| ExportEntry {
+            description: t.description.clone(),
+            timer_type: t.timer_type.clone(),
+            tags: t.tags.clone(),
+            start: t.endtime - Duration::seconds(t.timeleft_secs as i64),
+            end: t.endtime,
+            duration_secs: t.timeleft_secs,
+        })
+        .collect()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(entries: &[ExportEntry]) -> String {
+    let mut csv = String::from("description,type,tags,start,end,duration\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{:02}:{:02}:{:02}\n",
+            csv_field(&entry.description),
+            csv_field(entry.timer_type.as_deref().unwrap_or("")),
+            csv_field(&entry.tags.join(" ")),
+            entry.start.format("%Y-%m-%d %H:%M:%S"),
+            entry.end.format("%Y-%m-%d %H:%M:%S"),
+            entry.duration_secs / 3600,
+            (entry.duration_secs % 3600) / 60,
+            entry.duration_secs % 60
+        ));
+    }
+    csv
+}
+
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn ics_time(time: &DateTime<Local>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Folds a content line into lines of at most 75 octets, the continuation lines start with a
+/// space (RFC 5545, 3.1). Characters are not split.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+/// Calendar with one VEVENT per entry, lines end with CRLF and are folded as required by
+/// RFC 5545
+pub fn to_ics(entries: &[ExportEntry]) -> String {
+    let now = ics_time(&Local::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//multitimer-tui//EN".to_string(),
+    ];
+    for (i, entry) in entries.iter().enumerate() {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:{}-{}@multitimer-tui",
+            ics_time(&entry.start),
+            i
+        ));
+        lines.push(format!("DTSTAMP:{}", now));
+        lines.push(format!("DTSTART:{}", ics_time(&entry.start)));
+        lines.push(format!("DTEND:{}", ics_time(&entry.end)));
+        lines.push(format!("SUMMARY:{}", ics_text(&entry.description)));
+        let categories: Vec<String> = entry
+            .timer_type
+            .iter()
+            .chain(&entry.tags)
+            .map(|c| ics_text(c))
+            .collect();
+        if !categories.is_empty() {
+            lines.push(format!("CATEGORIES:{}", categories.join(",")));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines
+        .iter()
+        .map(|line| fold_ics_line(line) + "\r\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(description: &str, tags: &[&str]) -> ExportEntry {
+        ExportEntry {
+            description: description.to_string(),
+            timer_type: Some("focus".to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            start: Local.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap(),
+            end: Local.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap(),
+            duration_secs: 1505,
+        }
+    }
+
+    fn history_entry(outcome: Outcome, ticked_secs: u64) -> HistoryEntry {
+        HistoryEntry {
+            description: "Report".to_string(),
+            timer_type: None,
+            tags: Vec::new(),
+            outcome,
+            planned_secs: 1500,
+            started_at: Some(Local.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap()),
+            ended_at: Local.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap(),
+            pause_count: 1,
+            paused_secs: 600,
+            repeats: 0,
+            ticked_secs,
+            pomodoro_cycle: None,
+            snoozes: 0,
+            overtime_secs: 0,
+        }
+    }
+
+    #[test]
+    fn quotes_csv_fields_with_separators() {
+        let csv = to_csv(&[entry("Call \"Bob\", then\nwrite", &["a", "b"])]);
+        assert_eq!(
+            csv,
+            "description,type,tags,start,end,duration\n\
+             \"Call \"\"Bob\"\", then\nwrite\",focus,a b,2026-03-01 09:00:00,2026-03-01 10:00:00,00:25:05\n"
+        );
+    }
+
+    #[test]
+    fn leaves_plain_csv_fields_unquoted() {
+        assert_eq!(csv_field("Report"), "Report");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+    }
+
+    #[test]
+    fn escapes_ics_text() {
+        assert_eq!(ics_text("a,b;c\\d\ne"), r"a\,b\;c\\d\ne");
+        let ics = to_ics(&[entry("Plan; review, ship", &["x,y"])]);
+        assert!(ics.contains("\r\nSUMMARY:Plan\\; review\\, ship\r\n"));
+        assert!(ics.contains("\r\nCATEGORIES:focus,x\\,y\r\n"));
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn folds_long_ics_lines_at_75_octets() {
+        let description = "ä".repeat(60);
+        let ics = to_ics(&[entry(&description, &[])]);
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= 75, "{} octets: {}", line.len(), line);
+        }
+        // unfolding gives back the original line
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains(&format!("\r\nSUMMARY:{}\r\n", description)));
+        assert_eq!(fold_ics_line("BEGIN:VEVENT"), "BEGIN:VEVENT");
+    }
+
+    #[test]
+    fn exports_only_completed_timers_with_their_ticked_time() {
+        let history = [
+            history_entry(Outcome::Completed, 1500),
+            history_entry(Outcome::Skipped, 300),
+            history_entry(Outcome::Interrupted, 600),
+            // not the hour between start and end
+            history_entry(Outcome::Completed, 2400),
+        ];
+        let entries = history_entries(&history, None, None);
+        let durations: Vec<u64> = entries.iter().map(|e| e.duration_secs).collect();
+        assert_eq!(durations, vec![1500, 2400]);
+        let day = NaiveDate::from_ymd_opt(2026, 3, 2);
+        assert!(history_entries(&history, day, None).is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::time::SystemTime;
+
+use crate::timer::Timer;
+
+pub const HISTORY_FILE: &str = "history.jsonl";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Completed,
+    Skipped,
+    Removed,
+    /// A started Pomodoro work timer that was skipped or removed
+    Interrupted,
+}
+
+/// One line of the history file, written when a timer is skipped or removed, or when the user
+/// moves on after it was completed
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    pub description: String,
+    pub timer_type: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub outcome: Outcome,
+    pub planned_secs: u64,
+    pub started_at: Option<DateTime<Local>>,
+    pub ended_at: DateTime<Local>,
+    pub pause_count: u64,
+    pub paused_secs: u64,
+    pub repeats: u64,
+    /// Seconds the timer counted down
+    pub ticked_secs: u64,
+    #[serde(default)]
+    pub pomodoro_cycle: Option<u64>,
+    #[serde(default)]
+    pub snoozes: u64,
+    /// Time between the end of the timer and the user moving on
+    #[serde(default)]
+    pub overtime_secs: u64,
+}
+
+impl HistoryEntry {
+    pub fn from_timer(timer: &Timer, outcome: Outcome) -> Self {
+        Self {
+            description: timer.description.clone(),
+            timer_type: timer.timer_type.clone(),
+            tags: timer.tags.clone(),
+            outcome,
+            planned_secs: timer.initial_time,
+            started_at: timer.started_at,
+            ended_at: timer
+                .finished_at
+                .filter(|_| outcome == Outcome::Completed)
+                .unwrap_or_else(Local::now),
+            pause_count: timer.pause_count,
+            paused_secs: timer.paused_secs,
+            repeats: timer.repeats_done,
+            ticked_secs: timer.ticked_secs,
+            pomodoro_cycle: timer.pomodoro_cycle,
+            snoozes: timer.snoozes,
+            overtime_secs: if timer.in_overtime {
+                timer.overtime_secs()
+            } else {
+                0
+            },
+        }
+    }
+
+    /// Seconds the timer actually ran, without the pauses and the time the app was closed
+    pub fn focused_secs(&self) -> u64 {
+        self.ticked_secs
+    }
+}
+
+pub fn append_history_entry(entry: &HistoryEntry) -> std::io::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(HISTORY_FILE)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+}
+
+pub fn record_timer(timer: &Timer, outcome: Outcome) -> std::io::Result<()> {
+    append_history_entry(&HistoryEntry::from_timer(timer, outcome))
+}
+
+/// Reads all entries of the history file, lines that can't be parsed are skipped
+pub fn read_history() -> std::io::Result<Vec<HistoryEntry>> {
+    let file = match std::fs::File::open(HISTORY_FILE) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// The entries of the history file for the Statistics tab, read again when the file changes
+#[derive(Default)]
+pub struct HistoryCache {
+    pub entries: Vec<HistoryEntry>,
+    /// Why the file couldn't be read, the entries are those of the last successful read
+    pub error: Option<String>,
+    /// Modification time and size of the file at the last read, None before the first read
+    read_state: Option<Option<(SystemTime, u64)>>,
+}
+
+impl HistoryCache {
+    /// Reads the history file if it changed since the last read
+    pub fn refresh(&mut self) {
+        let state = std::fs::metadata(HISTORY_FILE)
+            .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+            .ok();
+        if self.read_state == Some(state) {
+            return;
+        }
+        self.read_state = Some(state);
+        match read_history() {
+            Ok(entries) => {
+                self.entries = entries;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Could not read {}: {}", HISTORY_FILE, e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::Configuration;
+    use crate::timer_logic::{count_global_pause, tick_timers};
+
+    #[test]
+    fn counts_pauses_only_after_the_start() {
+        let mut timer = Timer::new("Report".to_string(), 60, true, None);
+        // pausing a timer that never ran is no interruption
+        timer.toggle_pause();
+        timer.paused_or_tick();
+        timer.toggle_pause();
+        assert_eq!((timer.pause_count, timer.paused_secs), (0, 0));
+
+        timer.paused_or_tick();
+        timer.paused_or_tick();
+        timer.toggle_pause();
+        for _ in 0..3 {
+            timer.paused_or_tick();
+        }
+        timer.toggle_pause();
+        timer.paused_or_tick();
+
+        let entry = HistoryEntry::from_timer(&timer, Outcome::Removed);
+        assert!(entry.started_at.is_some());
+        assert_eq!(entry.pause_count, 1);
+        assert_eq!(entry.paused_secs, 3);
+        assert_eq!(entry.ticked_secs, 3);
+        assert_eq!(entry.focused_secs(), 3);
+    }
+
+    #[test]
+    fn counts_the_global_pause_of_the_running_timers() {
+        let mut config = Configuration::new();
+        config
+            .timers
+            .push(Timer::new("Started".to_string(), 60, true, None));
+        config
+            .timers
+            .push(Timer::new("Not started".to_string(), 60, false, None));
+        config.update_timers();
+        config.timers[0].tick();
+
+        count_global_pause(&mut config);
+        assert!(!tick_timers(&mut config, true));
+        assert!(!tick_timers(&mut config, true));
+
+        let counts: Vec<(u64, u64, u64)> = config
+            .timers
+            .iter()
+            .map(|t| (t.pause_count, t.paused_secs, t.ticked_secs))
+            .collect();
+        assert_eq!(counts, [(1, 2, 1), (0, 0, 0)]);
+    }
+
+    #[test]
+    fn records_overtime_apart_from_the_counted_down_time() {
+        let mut timer = Timer::new("Report".to_string(), 60, true, None);
+        timer.ticked_secs = 60;
+        timer.timeleft_secs = 0;
+        timer.started_at = Some(Local::now() - chrono::Duration::seconds(150));
+        timer.finished_at = Some(Local::now() - chrono::Duration::seconds(90));
+        timer.in_overtime = true;
+
+        let entry = HistoryEntry::from_timer(&timer, Outcome::Completed);
+        assert!((90..=91).contains(&entry.overtime_secs));
+        assert_eq!(entry.focused_secs(), 60);
+        assert_eq!(Some(entry.ended_at), timer.finished_at);
+
+        // a timer whose overtime has ended, e.g. when the app was started again, has none
+        timer.in_overtime = false;
+        let entry = HistoryEntry::from_timer(&timer, Outcome::Completed);
+        assert_eq!(entry.overtime_secs, 0);
+    }
+}
@@ -0,0 +1,211 @@
+use std::path::Path;
+
+use crate::configuration::Configuration;
+use crate::timer::{split_tags, Timer};
+
+/// An open to-do item read from a todo.txt file or a Markdown checklist
+pub struct TodoItem {
+    pub description: String,
+    pub minutes: Option<u64>,
+    pub timer_type: Option<String>,
+    pub tags: Vec<String>,
+    pub priority: Option<char>,
+}
+
+/// Parses durations like `25m`, `1h`, `1h30m` or plain minutes
+fn parse_minutes(value: &str) -> Option<u64> {
+    if let Ok(minutes) = value.parse::<u64>() {
+        return Some(minutes);
+    }
+    let mut minutes = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' => {
+                let n: u64 = number.parse().ok()?;
+                minutes += if c == 'h' { n * 60 } else { n };
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    if number.is_empty() && minutes > 0 {
+        Some(minutes)
+    } else {
+        None
+    }
+}
+
+/// Reads the item text after the checkbox or completion marker has been removed. Duration
+/// tokens (`~25m`, `est:25`) are taken out of the description, as is the first
+/// `+project`, `@context` or `#tag` that names a timer type. Other `#tags` become tags.
+fn parse_item(text: &str, config: &Configuration) -> Option<TodoItem> {
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    let priority = match words.first() {
+        Some(word) if word.len() == 3 && word.starts_with('(') && word.ends_with(')') => {
+            let p = word.chars().nth(1).filter(|c| c.is_ascii_uppercase());
+            if p.is_some() {
+                words.remove(0);
+            }
+            p
+        }
+        _ => None,
+    };
+
+    let mut minutes = None;
+    let mut timer_type = None;
+    let mut description = Vec::new();
+    for word in words {
+        let duration = word
+            .strip_prefix('~')
+            .or_else(|| word.strip_prefix("est:"))
+            .and_then(parse_minutes);
+        if duration.is_some() && minutes.is_none() {
+            minutes = duration;
+            continue;
+        }
+        let type_name = word
+            .strip_prefix(['+', '@', '#'])
+            .map(|name| name.to_lowercase())
+            .filter(|name| config.timer_colors.contains_key(name));
+        if type_name.is_some() && timer_type.is_none() {
+            timer_type = type_name;
+            continue;
+        }
+        description.push(word);
+    }
+    let (description, tags) = split_tags(&description.join(" "));
+    if description.is_empty() {
+        return None;
+    }
+    Some(TodoItem {
+        description,
+        minutes,
+        timer_type,
+        tags,
+        priority,
+    })
+}
+
+/// Open `- [ ]` / `* [ ]` items of a Markdown checklist
+pub fn parse_markdown(content: &str, config: &Configuration) -> Vec<TodoItem> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            line.strip_prefix("- [ ]")
+                .or_else(|| line.strip_prefix("* [ ]"))
+                .and_then(|text| parse_item(text, config))
+        })
+        .collect()
+}
+
+/// Open items of a todo.txt file, completed items start with `x `. A creation date after the
+/// priority is dropped from the description.
+pub fn parse_todo_txt(content: &str, config: &Configuration) -> Vec<TodoItem> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with("x "))
+        .filter_map(|line| {
+            let without_date: Vec<&str> = line
+                .split_whitespace()
+                .enumerate()
+                .filter(|(i, word)| {
+                    !(*i <= 1 && chrono::NaiveDate::parse_from_str(word, "%Y-%m-%d").is_ok())
+                })
+                .map(|(_, word)| word)
+                .collect();
+            parse_item(&without_date.join(" "), config)
+        })
+        .collect()
+}
+
+/// Adds a timer for every open item of the file that has no timer with the same description
+/// yet, items with a higher priority first. Returns how many timers were added.
+pub fn import_file(path: &str, config: &mut Configuration) -> Result<usize, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    let mut items = if extension == "md" || extension == "markdown" {
+        parse_markdown(&content, config)
+    } else {
+        parse_todo_txt(&content, config)
+    };
+    // items without priority come last, the sort keeps the file order otherwise
+    items.sort_by_key(|item| item.priority.unwrap_or('~'));
+    // timers added to the top end up in reverse order
+    if config.reverseadding {
+        items.reverse();
+    }
+
+    let mut imported = 0;
+    for item in items {
+        if config
+            .timers
+            .iter()
+            .any(|t| t.description == item.description)
+        {
+            continue;
+        }
+        let minutes = item.minutes.unwrap_or(config.pomodoro_time);
+        let mut timer = Timer::new(item.description, minutes * 60, true, item.timer_type);
+        timer.tags = item.tags;
+        config.add_timer_to_config(timer, false);
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_minutes_and_hours() {
+        assert_eq!(parse_minutes("25"), Some(25));
+        assert_eq!(parse_minutes("25m"), Some(25));
+        assert_eq!(parse_minutes("1h"), Some(60));
+        assert_eq!(parse_minutes("1h30m"), Some(90));
+    }
+
+    #[test]
+    fn rejects_other_durations() {
+        assert_eq!(parse_minutes(""), None);
+        assert_eq!(parse_minutes("m"), None);
+        assert_eq!(parse_minutes("0m"), None);
+        assert_eq!(parse_minutes("1h30"), None);
+        assert_eq!(parse_minutes("25min"), None);
+    }
+
+    #[test]
+    fn takes_priority_duration_and_type_out_of_the_description() {
+        let config = Configuration::new();
+        let item = parse_item("(A) Write report ~1h30m +Focus #clientA", &config).unwrap();
+        assert_eq!(item.priority, Some('A'));
+        assert_eq!(item.minutes, Some(90));
+        assert_eq!(item.timer_type.as_deref(), Some("focus"));
+        assert_eq!(item.tags, vec!["clientA".to_string()]);
+        assert_eq!(item.description, "Write report");
+    }
+
+    #[test]
+    fn keeps_words_that_are_no_type_or_duration() {
+        let config = Configuration::new();
+        let item = parse_item("(a) Call Bob est:15 ~20 @home +urgent", &config).unwrap();
+        // a lowercase priority is part of the description, only the first duration counts
+        assert_eq!(item.priority, None);
+        assert_eq!(item.minutes, Some(15));
+        assert_eq!(item.timer_type.as_deref(), Some("urgent"));
+        assert_eq!(item.description, "(a) Call Bob ~20 @home");
+    }
+
+    #[test]
+    fn skips_items_without_description() {
+        let config = Configuration::new();
+        assert!(parse_item("(B) ~25m #focus", &config).is_none());
+        assert!(parse_item("", &config).is_none());
+    }
+}
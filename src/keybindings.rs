@@ -0,0 +1,594 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Where a key is pressed. The bindings of `Global` apply everywhere, except that plain
+/// characters are typed in `Input`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Context {
+    Global,
+    /// The input line of the Timer tab, the search, and the type names and typed values in
+    /// the Config tab
+    Input,
+    Timer,
+    Focus,
+    Sets,
+    Config,
+    TimerTypes,
+    ColorPicker,
+    Statistics,
+}
+
+pub const CONTEXTS: [Context; 9] = [
+    Context::Global,
+    Context::Input,
+    Context::Timer,
+    Context::Focus,
+    Context::Sets,
+    Context::Config,
+    Context::TimerTypes,
+    Context::ColorPicker,
+    Context::Statistics,
+];
+
+impl Context {
+    /// Name of the section in the "keybindings" of config.json
+    pub fn name(&self) -> &'static str {
+        match self {
+            Context::Global => "global",
+            Context::Input => "input",
+            Context::Timer => "timer",
+            Context::Focus => "focus",
+            Context::Sets => "sets",
+            Context::Config => "config",
+            Context::TimerTypes => "types",
+            Context::ColorPicker => "color_picker",
+            Context::Statistics => "statistics",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Context::Global => "Everywhere",
+            Context::Input => "Input line, search and type names",
+            Context::Timer => "Timer tab",
+            Context::Focus => "Focus mode",
+            Context::Sets => "Sets tab",
+            Context::Config => "Config tab",
+            Context::TimerTypes => "Timer types in the Config tab",
+            Context::ColorPicker => "Color picker",
+            Context::Statistics => "Statistics tab",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    Quit,
+    NextTab,
+    Run,
+    Cancel,
+    CursorLeft,
+    CursorRight,
+    HistoryPrevious,
+    HistoryNext,
+    DeleteChar,
+    Command,
+    ToggleHelp,
+    BigDigits,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    PauseAll,
+    SelectNext,
+    SelectPrevious,
+    PageDown,
+    PageUp,
+    OtherColumn,
+    Mark,
+    Clear,
+    Rename,
+    ToggleChecklist,
+    AddSubtask,
+    CheckSubtask,
+    MoveDown,
+    MoveUp,
+    Delete,
+    Increase,
+    Decrease,
+    Pause,
+    Skip,
+    Acknowledge,
+    Snooze,
+    FocusMode,
+    TableView,
+    Sort,
+    ReverseSort,
+    LeaveFocus,
+    SaveSet,
+    ApplySet,
+    PickColor,
+    EditTypes,
+    ClearValue,
+    EditValue,
+    Save,
+    NextValue,
+    PreviousValue,
+    LeaveTypes,
+    AddType,
+    Pick,
+    NextRow,
+    PreviousRow,
+    HexValue,
+    NextRange,
+    PreviousRange,
+    GroupByTag,
+}
+
+/// An action of a context with its name in config.json, its default keys and its help text
+struct Binding {
+    context: Context,
+    action: Action,
+    name: &'static str,
+    keys: &'static str,
+    description: &'static str,
+}
+
+const fn binding(
+    context: Context,
+    action: Action,
+    name: &'static str,
+    keys: &'static str,
+    description: &'static str,
+) -> Binding {
+    Binding {
+        context,
+        action,
+        name,
+        keys,
+        description,
+    }
+}
+
+#[rustfmt::skip]
+const DEFAULT_BINDINGS: &[Binding] = &[
+    binding(Context::Global, Action::Quit, "quit", "q", "Quit"),
+    binding(Context::Global, Action::NextTab, "next_tab", "Tab", "Switch to the next tab"),
+    binding(Context::Input, Action::Run, "run", "Enter", "Run the command, keep the search or save the name"),
+    binding(Context::Input, Action::Cancel, "cancel", "Esc", "Clear the input"),
+    binding(Context::Input, Action::CursorLeft, "cursor_left", "Left", "Move the cursor left"),
+    binding(Context::Input, Action::CursorRight, "cursor_right", "Right", "Move the cursor right"),
+    binding(Context::Input, Action::HistoryPrevious, "history_previous", "Up", "Previous command"),
+    binding(Context::Input, Action::HistoryNext, "history_next", "Down", "Next command"),
+    binding(Context::Input, Action::DeleteChar, "delete_char", "Backspace", "Delete the character before the cursor"),
+    binding(Context::Timer, Action::Command, "command", ":", "Enter a command in the input line"),
+    binding(Context::Timer, Action::ToggleHelp, "toggle_help", "h", "Show or hide this help, it scrolls like the timers"),
+    binding(Context::Timer, Action::SelectNext, "select_next", "j, Down", "Select the next timer in the column"),
+    binding(Context::Timer, Action::SelectPrevious, "select_previous", "k, Up", "Select the previous timer in the column"),
+    binding(Context::Timer, Action::OtherColumn, "other_column", "Left, Right", "Select a timer in the other column"),
+    binding(Context::Timer, Action::PageDown, "page_down", "PageDown", "Move the selection one page down"),
+    binding(Context::Timer, Action::PageUp, "page_up", "PageUp", "Move the selection one page up"),
+    binding(Context::Timer, Action::MoveDown, "move_down", "J", "Move the selected timer down"),
+    binding(Context::Timer, Action::MoveUp, "move_up", "K", "Move the selected timer up"),
+    binding(Context::Timer, Action::Delete, "delete", "d, Delete", "Delete the selected timers"),
+    binding(Context::Timer, Action::Increase, "increase", "+", "Add the adjust step (minutes) to the selected timers"),
+    binding(Context::Timer, Action::Decrease, "decrease", "-", "Subtract the adjust step from the selected timers"),
+    binding(Context::Timer, Action::Rename, "rename", "r", "Rename the selected timer"),
+    binding(Context::Timer, Action::Pause, "pause", "p", "Pause or resume the selected timers"),
+    binding(Context::Timer, Action::PauseAll, "pause_all", "Space", "Pause or resume all timers"),
+    binding(Context::Timer, Action::Skip, "skip", "s", "Skip the selected timers"),
+    binding(Context::Timer, Action::Acknowledge, "acknowledge", "Enter", "Continue after timers waiting for an acknowledgement"),
+    binding(Context::Timer, Action::Snooze, "snooze", "z", "Snooze the selected or the last finished timer"),
+    binding(Context::Timer, Action::Search, "search", "/", "Search the timers by description, type, tag or id"),
+    binding(Context::Timer, Action::NextMatch, "next_match", "n", "Select the next match of the search"),
+    binding(Context::Timer, Action::PreviousMatch, "previous_match", "N", "Select the previous match of the search"),
+    binding(Context::Timer, Action::ToggleChecklist, "toggle_checklist", "e", "Show or hide the checklist of the selected timer"),
+    binding(Context::Timer, Action::AddSubtask, "add_subtask", "a", "Add an item to the checklist of the selected timer"),
+    binding(Context::Timer, Action::CheckSubtask, "check_subtask", "x", "Check an item of the checklist of the selected timer"),
+    binding(Context::Timer, Action::Mark, "mark", "v", "Mark the selected timer for bulk operations"),
+    binding(Context::Timer, Action::Clear, "clear", "Esc", "Close the help, clear the search or the marks"),
+    binding(Context::Timer, Action::BigDigits, "big_digits", "b", "Show or hide the big-digit countdown"),
+    binding(Context::Timer, Action::FocusMode, "focus_mode", "f", "Enter the focus mode"),
+    binding(Context::Timer, Action::TableView, "table_view", "t", "Switch between the timer boxes and the table view"),
+    binding(Context::Timer, Action::Sort, "sort", "o", "Sort the table view by the next column"),
+    binding(Context::Timer, Action::ReverseSort, "reverse_sort", "O", "Reverse the order of the table view"),
+    binding(Context::Focus, Action::Pause, "pause", "p", "Pause or resume the timer"),
+    binding(Context::Focus, Action::PauseAll, "pause_all", "Space", "Pause or resume all timers"),
+    binding(Context::Focus, Action::Skip, "skip", "s", "Skip the timer"),
+    binding(Context::Focus, Action::Acknowledge, "acknowledge", "Enter", "Continue after a timer waiting for an acknowledgement"),
+    binding(Context::Focus, Action::LeaveFocus, "leave_focus", "f, Esc", "Leave the focus mode"),
+    binding(Context::Sets, Action::SelectNext, "select_next", "Down", "Select the next set"),
+    binding(Context::Sets, Action::SelectPrevious, "select_previous", "Up", "Select the previous set"),
+    binding(Context::Sets, Action::ApplySet, "apply_set", "Enter", "Replace the timers with the selected set"),
+    binding(Context::Sets, Action::SaveSet, "save_set", "Esc", "Save the timers as a new set"),
+    binding(Context::Sets, Action::Delete, "delete", "Delete, Backspace", "Delete the selected set"),
+    binding(Context::Config, Action::SelectNext, "select_next", "Down", "Select the next setting"),
+    binding(Context::Config, Action::SelectPrevious, "select_previous", "Up", "Select the previous setting"),
+    binding(Context::Config, Action::NextValue, "next_value", "Right", "Change the setting to the next value"),
+    binding(Context::Config, Action::PreviousValue, "previous_value", "Left", "Change the setting to the previous value"),
+    binding(Context::Config, Action::ClearValue, "clear_value", "Esc", "Reset the setting to its default value"),
+    binding(Context::Config, Action::EditValue, "edit_value", "e", "Type a value for the selected setting"),
+    binding(Context::Config, Action::Save, "save", "Enter", "Save the configuration"),
+    binding(Context::Config, Action::PickColor, "pick_color", "c", "Pick a color for the selected color setting"),
+    binding(Context::Config, Action::EditTypes, "edit_types", "t", "Edit the timer types"),
+    binding(Context::TimerTypes, Action::SelectNext, "select_next", "j, Down", "Select the next timer type"),
+    binding(Context::TimerTypes, Action::SelectPrevious, "select_previous", "k, Up", "Select the previous timer type"),
+    binding(Context::TimerTypes, Action::NextValue, "next_value", "Right", "Change the type to the next color"),
+    binding(Context::TimerTypes, Action::PreviousValue, "previous_value", "Left", "Change the type to the previous color"),
+    binding(Context::TimerTypes, Action::AddType, "add_type", "a", "Add a timer type"),
+    binding(Context::TimerTypes, Action::Rename, "rename", "r", "Rename the selected timer type"),
+    binding(Context::TimerTypes, Action::Delete, "delete", "d, Delete", "Delete the selected timer type"),
+    binding(Context::TimerTypes, Action::PickColor, "pick_color", "c", "Pick a color for the selected timer type"),
+    binding(Context::TimerTypes, Action::LeaveTypes, "leave_types", "t, Esc", "Go back to the settings"),
+    binding(Context::ColorPicker, Action::NextValue, "next_value", "Right", "Select the next color"),
+    binding(Context::ColorPicker, Action::PreviousValue, "previous_value", "Left", "Select the previous color"),
+    binding(Context::ColorPicker, Action::NextRow, "next_row", "Down", "Select the color below"),
+    binding(Context::ColorPicker, Action::PreviousRow, "previous_row", "Up", "Select the color above"),
+    binding(Context::ColorPicker, Action::HexValue, "hex_value", "#", "Type a hex value instead (#rrggbb)"),
+    binding(Context::ColorPicker, Action::Pick, "pick", "Enter", "Use the selected color"),
+    binding(Context::ColorPicker, Action::Cancel, "cancel", "Esc", "Close the color picker"),
+    binding(Context::Statistics, Action::NextRange, "next_range", "Right", "Show the next date range"),
+    binding(Context::Statistics, Action::PreviousRange, "previous_range", "Left", "Show the previous date range"),
+    binding(Context::Statistics, Action::GroupByTag, "group_by_tag", "g", "Group the time per type or per tag"),
+];
+
+/// A key with the Ctrl and Alt modifiers, e.g. "Ctrl+c", "J" or "PageDown"
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 16] = [
+    ("Space", KeyCode::Char(' ')),
+    ("Tab", KeyCode::Tab),
+    ("BackTab", KeyCode::BackTab),
+    ("Enter", KeyCode::Enter),
+    ("Esc", KeyCode::Esc),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+];
+
+impl Key {
+    /// Parses a key as written in config.json, named keys and modifiers ignore case
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                modifiers |= KeyModifiers::ALT;
+                rest = &rest[4..];
+            } else {
+                break;
+            }
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|(_, code)| *code)
+                .or_else(|| {
+                    let number = rest.strip_prefix(['F', 'f'])?.parse::<u8>().ok()?;
+                    (1..=12).contains(&number).then_some(KeyCode::F(number))
+                })
+                .ok_or_else(|| format!("Unknown key '{}'", s))?,
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether the key would type a character into an input
+    fn is_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+
+    fn matches(&self, key: &KeyEvent) -> bool {
+        let modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        self.code == key.code && self.modifiers == modifiers
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, named)| *named == code)
+                    .map_or("?", |(name, _)| name);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+fn parse_keys(keys: &str) -> Result<Vec<Key>, String> {
+    keys.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(Key::parse)
+        .collect()
+}
+
+/// The effective keys of all actions: the defaults with the "keybindings" of config.json on top
+pub struct KeyMap {
+    bindings: Vec<(Context, Action, Vec<Key>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: DEFAULT_BINDINGS
+                .iter()
+                .map(|b| (b.context, b.action, parse_keys(b.keys).unwrap()))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Applies the bindings of config.json, e.g. `{"timer": {"pause": "p, P"}}`. An empty
+    /// value unbinds the action. Unknown names and keys that are bound twice are errors.
+    pub fn load(overrides: &BTreeMap<String, BTreeMap<String, String>>) -> Result<Self, String> {
+        let mut map = Self::default();
+        let mut errors = Vec::new();
+        for (context_name, actions) in overrides {
+            let Some(context) = CONTEXTS.iter().find(|c| c.name() == context_name) else {
+                errors.push(format!("Unknown keybinding section '{}'", context_name));
+                continue;
+            };
+            for (action_name, keys) in actions {
+                let Some(binding) = DEFAULT_BINDINGS
+                    .iter()
+                    .position(|b| b.context == *context && b.name == action_name)
+                else {
+                    errors.push(format!("Unknown action '{}.{}'", context_name, action_name));
+                    continue;
+                };
+                match parse_keys(keys) {
+                    Ok(keys) => map.bindings[binding].2 = keys,
+                    Err(e) => errors.push(format!("{}.{}: {}", context_name, action_name, e)),
+                }
+            }
+        }
+        errors.extend(map.conflicts());
+        if errors.is_empty() {
+            Ok(map)
+        } else {
+            Err(format!("Keybindings: {}", errors.join("; ")))
+        }
+    }
+
+    /// Keys that trigger more than one action in a context, and characters bound in inputs
+    fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for context in CONTEXTS.iter().filter(|c| **c != Context::Global) {
+            let mut seen: Vec<(Key, String)> = Vec::new();
+            for (i, (binding_context, _, keys)) in self.bindings.iter().enumerate() {
+                let global = *binding_context == Context::Global;
+                if *binding_context != *context && !global {
+                    continue;
+                }
+                let name = format!("{}.{}", binding_context.name(), DEFAULT_BINDINGS[i].name);
+                for key in keys {
+                    if *context == Context::Input && key.is_text() {
+                        if !global {
+                            conflicts.push(format!("{} can't be typed in {}", key, name));
+                        }
+                        continue;
+                    }
+                    match seen.iter().find(|(seen_key, _)| seen_key == key) {
+                        Some((_, other)) => {
+                            let conflict = format!("'{}' is bound to {} and {}", key, other, name);
+                            if !conflicts.contains(&conflict) {
+                                conflicts.push(conflict);
+                            }
+                        }
+                        None => seen.push((*key, name.clone())),
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// The action of the key in the context or, if it has none there, everywhere
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        let find = |context: Context| {
+            self.bindings
+                .iter()
+                .find(|(c, _, keys)| *c == context && keys.iter().any(|k| k.matches(key)))
+                .map(|(_, action, _)| *action)
+        };
+        find(context).or_else(|| {
+            let typed = matches!(key.code, KeyCode::Char(_))
+                && !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+            if context == Context::Input && typed {
+                None
+            } else {
+                find(Context::Global)
+            }
+        })
+    }
+
+    /// The keys of an action for the hints, e.g. "j/Down", or "unbound"
+    pub fn keys(&self, context: Context, action: Action) -> String {
+        self.bindings
+            .iter()
+            .find(|(c, a, _)| *c == context && *a == action)
+            .filter(|(_, _, keys)| !keys.is_empty())
+            .map_or("unbound".to_string(), |(_, _, keys)| {
+                keys.iter()
+                    .map(|k| k.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+    }
+
+    /// A key event that triggers the action, e.g. for the mouse wheel
+    pub fn first_key(&self, context: Context, action: Action) -> Option<KeyEvent> {
+        self.bindings
+            .iter()
+            .find(|(c, a, _)| *c == context && *a == action)
+            .and_then(|(_, _, keys)| keys.first())
+            .map(|key| KeyEvent::new(key.code, key.modifiers))
+    }
+
+    /// The bindings of all contexts for the help popup
+    pub fn help_lines(&self) -> Vec<String> {
+        let mut lines = vec!["Keys (change them under \"keybindings\" in config.json)".to_string()];
+        for context in CONTEXTS {
+            lines.push(String::new());
+            lines.push(format!("{} [{}]", context.title(), context.name()));
+            for (i, (c, action, _)) in self.bindings.iter().enumerate() {
+                if *c == context {
+                    lines.push(format!(
+                        "{:<18}{:<18}{}",
+                        DEFAULT_BINDINGS[i].name,
+                        self.keys(context, *action),
+                        DEFAULT_BINDINGS[i].description
+                    ));
+                }
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Key {
+        Key { code, modifiers }
+    }
+
+    fn overrides(context: &str, action: &str, keys: &str) -> KeyMap {
+        let mut map = KeyMap::default();
+        let binding = DEFAULT_BINDINGS
+            .iter()
+            .position(|b| b.context.name() == context && b.name == action)
+            .unwrap();
+        map.bindings[binding].2 = parse_keys(keys).unwrap();
+        map
+    }
+
+    #[test]
+    fn parses_characters_and_named_keys() {
+        assert_eq!(
+            Key::parse("J"),
+            Ok(key(KeyCode::Char('J'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            Key::parse("+"),
+            Ok(key(KeyCode::Char('+'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            Key::parse("pagedown"),
+            Ok(key(KeyCode::PageDown, KeyModifiers::NONE))
+        );
+        assert_eq!(
+            Key::parse("Space"),
+            Ok(key(KeyCode::Char(' '), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            Key::parse("f12"),
+            Ok(key(KeyCode::F(12), KeyModifiers::NONE))
+        );
+    }
+
+    #[test]
+    fn parses_modifiers() {
+        assert_eq!(
+            Key::parse("Ctrl+c"),
+            Ok(key(KeyCode::Char('c'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            Key::parse("alt+CTRL+Left"),
+            Ok(key(
+                KeyCode::Left,
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        // a plus sign on its own is the key, not a modifier
+        assert_eq!(
+            Key::parse("Ctrl++"),
+            Ok(key(KeyCode::Char('+'), KeyModifiers::CONTROL))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        assert_eq!(Key::parse("F13"), Err("Unknown key 'F13'".to_string()));
+        assert_eq!(Key::parse("Ctrl+"), Err("Unknown key 'Ctrl+'".to_string()));
+        assert_eq!(Key::parse("Foo"), Err("Unknown key 'Foo'".to_string()));
+        assert!(parse_keys("j, Nope").is_err());
+    }
+
+    #[test]
+    fn displays_keys_as_they_are_parsed() {
+        for text in ["Ctrl+Alt+x", "Space", "PageDown", "F5", "J"] {
+            assert_eq!(Key::parse(text).unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn default_bindings_have_no_conflicts() {
+        assert!(KeyMap::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn finds_keys_bound_twice_in_a_context() {
+        let map = overrides("timer", "pause", "j");
+        assert_eq!(
+            map.conflicts(),
+            vec!["'j' is bound to timer.select_next and timer.pause".to_string()]
+        );
+    }
+
+    #[test]
+    fn finds_context_keys_that_hide_global_ones() {
+        let map = overrides("timer", "pause", "q");
+        assert_eq!(
+            map.conflicts(),
+            vec!["'q' is bound to global.quit and timer.pause".to_string()]
+        );
+    }
+
+    #[test]
+    fn finds_characters_bound_in_inputs() {
+        let map = overrides("input", "cancel", "x");
+        assert_eq!(
+            map.conflicts(),
+            vec!["x can't be typed in input.cancel".to_string()]
+        );
+        // the same key in another context is fine
+        assert!(overrides("sets", "delete", "x").conflicts().is_empty());
+    }
+}
@@ -1,8 +1,18 @@
+pub mod big_digits;
 pub mod color;
 pub mod configuration;
+pub mod export;
+pub mod history;
+pub mod import;
 pub mod input_field;
+pub mod keybindings;
+pub mod settings;
+pub mod statistics;
+pub mod taskwarrior;
+pub mod theme;
 pub mod timer;
 pub mod timer_logic;
+pub mod timer_types;
 pub mod ui;
 pub mod ui_states;
 pub mod utils;
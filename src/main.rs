@@ -3,7 +3,7 @@ use std::fs;
 use std::process::Command;
 
 use crossterm::{
-    event::{self, DisableMouseCapture, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -19,10 +19,30 @@ use std::{
 };
 
 use multitimer_tui::configuration::Configuration;
+use multitimer_tui::export::run_export;
 use multitimer_tui::input_field::InputField;
+use multitimer_tui::taskwarrior::wait_for_task_queue;
+use multitimer_tui::timer_logic::{
+    end_all_overtime, end_noticed_overtime, end_stale_overtime, tick_timers,
+};
 use multitimer_tui::ui;
 
 fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("export") {
+        let mut config = load_config();
+        config.update_timers();
+        let args: Vec<&str> = args[2..].iter().map(String::as_str).collect();
+        match run_export(&args, &config) {
+            Ok(path) => println!("Exported to {}", path),
+            Err(e) => {
+                eprintln!("Export failed: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     let os = env::consts::OS;
@@ -50,80 +70,75 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, tick_rate: Duration) -> io::Result<()> {
+fn load_config<'a>() -> Configuration<'a> {
+    fs::read_to_string("config.json")
+        .map(|data| serde_json::from_str(&data).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+fn run_app<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    tick_rate: Duration,
+) -> io::Result<()> {
     let mut last_tick = Instant::now();
     let mut input_field = InputField::new();
 
-    let mut config: Configuration = fs::read_to_string("config.json")
-        .map(|data| serde_json::from_str(&data).unwrap_or(Configuration::new(25, 5, 10)))
-        .unwrap_or(Configuration::new(25, 5, 10));
+    let mut config = load_config();
 
-    config.titles = vec!["Timer [1]", "Sets [2]", "Config [3]"];
+    config.titles = vec!["Timer [1]", "Sets [2]", "Config [3]", "Statistics [4]"];
     config.update_timers();
+    end_stale_overtime(&mut config);
+    if let Err(e) = config.apply_theme() {
+        config.status_message = Some(e);
+    }
+    if let Err(e) = config.apply_keybindings() {
+        config.status_message = Some(e);
+    }
 
     let mut pause_flag: bool = false;
+    let mut mouse_captured = false;
 
     let mut i = 0;
     loop {
+        if config.mouse_support != mouse_captured {
+            if config.mouse_support {
+                execute!(terminal.backend_mut(), EnableMouseCapture)?;
+            } else {
+                execute!(terminal.backend_mut(), DisableMouseCapture)?;
+            }
+            mouse_captured = config.mouse_support;
+        }
+
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
-            if !pause_flag {
-                let mut left_view_done = false;
-                let mut right_view_done = false;
-                let mut done_timers = Vec::new();
-                for (i, timer) in config.timers.iter_mut().enumerate() {
-                    let mut done = false;
-                    if timer.left_view && !left_view_done && timer.timeleft_secs != 0 {
-                        done = timer.tick();
-                        left_view_done = true;
-                    } else if !timer.left_view && !right_view_done && timer.timeleft_secs != 0 {
-                        done = timer.tick();
-                        right_view_done = true;
-                    }
-                    if done && config.move_finished_timer {
-                        done_timers.push(i);
-                    }
+            let finished_this_tick = tick_timers(&mut config, pause_flag);
+            if config.action_timeout != "None"
+                && finished_this_tick
+                && config.check_all_timers_done()
+            {
+                let os = env::consts::OS;
+                #[allow(clippy::zombie_processes)]
+                if os == "windows" && config.action_timeout == "Hibernate" {
+                    Command::new("rundll32.exe")
+                        .args(["powrprof.dll,SetSuspendState", "0,1,0"])
+                        .spawn()
+                        .expect("Sleeping computer failed");
+                } else if os == "linux" && config.action_timeout == "Hibernate" {
+                    Command::new("systemctl")
+                        .args(["suspend"])
+                        .spawn()
+                        .expect("Sleeping computer failed");
+                } else if os == "windows" && config.action_timeout == "Shutdown" {
+                    Command::new("shutdown")
+                        .args(["/s", "/t", "0"])
+                        .spawn()
+                        .expect("Shutting down failed");
+                } else if os == "linux" && config.action_timeout == "Shutdown" {
+                    Command::new("shutdown")
+                        .args(["-h", "now"])
+                        .spawn()
+                        .expect("Shutting down failed");
                 }
-
-                if config.move_finished_timer {
-                    for &i in done_timers.iter().rev() {
-                        let t = config.timers.remove(i);
-                        config.timers.push(t);
-                    }
-                    if !done_timers.is_empty() {
-                        config.update_timers();
-                    }
-                }
-
-                if config.action_timeout != "None"
-                    && (left_view_done || right_view_done)
-                    && config.check_all_timers_done()
-                {
-                    let os = env::consts::OS;
-                    if os == "windows" && config.action_timeout == "Hibernate" {
-                        Command::new("rundll32.exe")
-                            .args(["powrprof.dll,SetSuspendState", "0,1,0"])
-                            .spawn()
-                            .expect("Sleeping computer failed");
-                    } else if os == "linux" && config.action_timeout == "Hibernate" {
-                        Command::new("systemctl")
-                            .args(["suspend"])
-                            .spawn()
-                            .expect("Sleeping computer failed");
-                    } else if os == "windows" && config.action_timeout == "Shutdown" {
-                        Command::new("shutdown")
-                            .args(["/s", "/t", "0"])
-                            .spawn()
-                            .expect("Shutting down failed");
-                    } else if os == "linux" && config.action_timeout == "Shutdown" {
-                        Command::new("shutdown")
-                            .args(["-h", "now"])
-                            .spawn()
-                            .expect("Shutting down failed");
-                    }
-                }
-            } else {
-                config.update_timers();
             }
 
             terminal.draw(|f| ui::ui(f, &mut config, &input_field))?;
@@ -137,12 +152,26 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, tick_rate: Duration) -> io::R
             .checked_sub(last_tick.elapsed())
             .unwrap_or_else(|| Duration::from_secs(0));
         if crossterm::event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                if input_field.content.is_empty() && KeyCode::Char('q') == key.code {
-                    return Ok(());
-                } else {
+            match event::read()? {
+                Event::Key(key) => {
+                    end_noticed_overtime(&mut config);
                     ui::handle_key_press(key, &mut config, &mut input_field, &mut pause_flag)?;
+                    if config.quit {
+                        wait_for_task_queue(&mut config);
+                        config.close_notifications();
+                        end_all_overtime(&mut config);
+                        config.write_config_to_file()?;
+                        return Ok(());
+                    }
+                }
+                Event::Mouse(mouse) => {
+                    if mouse.kind == MouseEventKind::Moved {
+                        continue;
+                    }
+                    end_noticed_overtime(&mut config);
+                    ui::handle_mouse_event(mouse, &mut config, &mut input_field, &mut pause_flag)?;
                 }
+                _ => {}
             }
             terminal.draw(|f| ui::ui(f, &mut config, &input_field))?;
         }
@@ -0,0 +1,518 @@
+use std::fmt;
+
+use crate::color::AcceptedColors;
+use crate::configuration::Configuration;
+use crate::theme::theme_names;
+use crate::ui_states::{TaskwarriorFinish, TimerAction};
+use crate::utils::{cycle_custom_color, reverse_bool};
+
+/// The kind of value of a setting, it decides how the value is cycled and checked
+pub enum SettingKind {
+    Bool,
+    /// One of the names the function returns
+    Choice(fn() -> Vec<String>),
+    Number {
+        min: u64,
+        max: u64,
+    },
+    /// A color name, palette index or hex value, "Default" follows the darkmode setting if
+    /// it is allowed
+    Color {
+        allow_default: bool,
+    },
+    /// Any text that isn't empty, it can only be typed
+    Text,
+}
+
+/// A value that passed `Setting::check`, the kind of the setting decides the variant
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SettingValue {
+    Bool(bool),
+    Number(u64),
+    /// Choices, colors and text
+    Text(String),
+}
+
+impl fmt::Display for SettingValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingValue::Bool(value) => write!(f, "{}", value),
+            SettingValue::Number(value) => write!(f, "{}", value),
+            SettingValue::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<SettingValue> for bool {
+    fn from(value: SettingValue) -> bool {
+        matches!(value, SettingValue::Bool(true))
+    }
+}
+
+impl From<SettingValue> for u64 {
+    fn from(value: SettingValue) -> u64 {
+        match value {
+            SettingValue::Number(value) => value,
+            _ => 0,
+        }
+    }
+}
+
+impl From<SettingValue> for String {
+    fn from(value: SettingValue) -> String {
+        value.to_string()
+    }
+}
+
+/// A row of the Config tab. The values are edited as text and only stored in the
+/// configuration when they are saved.
+pub struct Setting {
+    /// Name of the field in config.json
+    pub name: &'static str,
+    pub label: &'static str,
+    pub kind: SettingKind,
+    /// The value of a new configuration and of settings missing in config.json
+    pub default: &'static str,
+    pub get: fn(&Configuration) -> String,
+    pub set: fn(&mut Configuration, SettingValue),
+}
+
+impl Setting {
+    /// The value after the current one, the first value if the current one isn't valid.
+    /// Numbers stop at the ends of their range, text stays as it is.
+    pub fn cycle(&self, value: &str, forward: bool) -> String {
+        match &self.kind {
+            SettingKind::Bool => reverse_bool(value),
+            SettingKind::Choice(names) => {
+                let names = names();
+                if names.is_empty() {
+                    return value.to_string();
+                }
+                let Some(i) = names.iter().position(|name| name == value) else {
+                    return names[0].clone();
+                };
+                let i = if forward {
+                    (i + 1) % names.len()
+                } else {
+                    (i + names.len() - 1) % names.len()
+                };
+                names[i].clone()
+            }
+            SettingKind::Number { min, max } => {
+                let number = value.parse::<u64>().unwrap_or(*min);
+                let number = if forward {
+                    number.saturating_add(1)
+                } else {
+                    number.saturating_sub(1)
+                };
+                number.clamp(*min, *max).to_string()
+            }
+            SettingKind::Color {
+                allow_default: true,
+            } => cycle_custom_color(value, forward),
+            SettingKind::Color {
+                allow_default: false,
+            } => match (AcceptedColors::parse(value), forward) {
+                (Some(color), true) => color.next_color().to_string(),
+                (Some(color), false) => color.previous_color().to_string(),
+                (None, _) => AcceptedColors::Black.to_string(),
+            },
+            SettingKind::Text => value.to_string(),
+        }
+    }
+
+    /// The value as it is stored, an empty value is the default
+    pub fn check(&self, value: &str) -> Result<SettingValue, String> {
+        let value = value.trim();
+        let value = if value.is_empty() {
+            self.default
+        } else {
+            value
+        };
+        let checked = match &self.kind {
+            SettingKind::Bool => value.parse::<bool>().ok().map(SettingValue::Bool),
+            SettingKind::Number { min, max } => value
+                .parse::<u64>()
+                .ok()
+                .filter(|number| (*min..=*max).contains(number))
+                .map(SettingValue::Number),
+            SettingKind::Choice(names) => names()
+                .iter()
+                .any(|name| name == value)
+                .then(|| SettingValue::Text(value.to_string())),
+            SettingKind::Color { allow_default } => (AcceptedColors::parse(value).is_some()
+                || (*allow_default && value == "Default"))
+                .then(|| SettingValue::Text(value.to_string())),
+            SettingKind::Text => Some(SettingValue::Text(value.to_string())),
+        };
+        checked.ok_or(format!(
+            "Invalid value '{}' for {}, use {}",
+            value,
+            self.label,
+            self.accepted_values()
+        ))
+    }
+
+    /// The accepted values for messages
+    pub fn accepted_values(&self) -> String {
+        match &self.kind {
+            SettingKind::Bool => "true or false".to_string(),
+            SettingKind::Choice(names) => names().join(", "),
+            SettingKind::Number { min, max } => format!("a number from {} to {}", min, max),
+            SettingKind::Color {
+                allow_default: true,
+            } => format!("Default, {}", AcceptedColors::names()),
+            SettingKind::Color {
+                allow_default: false,
+            } => AcceptedColors::names(),
+            SettingKind::Text => "any text".to_string(),
+        }
+    }
+}
+
+fn timer_actions() -> Vec<String> {
+    [
+        TimerAction::None,
+        TimerAction::Hibernate,
+        TimerAction::Shutdown,
+    ]
+    .iter()
+    .map(|action| action.to_string())
+    .collect()
+}
+
+fn taskwarrior_finishes() -> Vec<String> {
+    [
+        TaskwarriorFinish::None,
+        TaskwarriorFinish::Done,
+        TaskwarriorFinish::Annotate,
+    ]
+    .iter()
+    .map(|finish| finish.to_string())
+    .collect()
+}
+
+/// The rows of the Config tab in the order they are shown
+pub static SETTINGS: [Setting; 24] = [
+    Setting {
+        name: "darkmode",
+        label: "Darkmode",
+        kind: SettingKind::Bool,
+        default: "true",
+        get: |config| config.darkmode.to_string(),
+        set: |config, value| config.darkmode = value.into(),
+    },
+    Setting {
+        name: "activecolor",
+        label: "Active Color",
+        kind: SettingKind::Color {
+            allow_default: false,
+        },
+        default: "Green",
+        get: |config| config.activecolor.clone(),
+        set: |config, value| config.activecolor = value.into(),
+    },
+    Setting {
+        name: "reverseadding",
+        label: "Reverse Adding of Timers",
+        kind: SettingKind::Bool,
+        default: "false",
+        get: |config| config.reverseadding.to_string(),
+        set: |config, value| config.reverseadding = value.into(),
+    },
+    Setting {
+        name: "move_finished_timer",
+        label: "Move Finished Timer to End",
+        kind: SettingKind::Bool,
+        default: "true",
+        get: |config| config.move_finished_timer.to_string(),
+        set: |config, value| config.move_finished_timer = value.into(),
+    },
+    Setting {
+        name: "action_timeout",
+        label: "Action After Timers Done",
+        kind: SettingKind::Choice(timer_actions),
+        default: "None",
+        get: |config| config.action_timeout.clone(),
+        set: |config, value| config.action_timeout = value.into(),
+    },
+    Setting {
+        name: "pomodoro_time",
+        label: "Pomodoro Time",
+        kind: SettingKind::Number { min: 1, max: 99 },
+        default: "25",
+        get: |config| config.pomodoro_time.to_string(),
+        set: |config, value| config.pomodoro_time = value.into(),
+    },
+    Setting {
+        name: "pomodoro_smallbreak",
+        label: "Pomodoro Small Break Time",
+        kind: SettingKind::Number { min: 1, max: 99 },
+        default: "5",
+        get: |config| config.pomodoro_smallbreak.to_string(),
+        set: |config, value| config.pomodoro_smallbreak = value.into(),
+    },
+    Setting {
+        name: "pomodoro_bigbreak",
+        label: "Pomodoro Big Break Time",
+        kind: SettingKind::Number { min: 1, max: 99 },
+        default: "10",
+        get: |config| config.pomodoro_bigbreak.to_string(),
+        set: |config, value| config.pomodoro_bigbreak = value.into(),
+    },
+    Setting {
+        name: "pomodoro_cycles",
+        label: "Pomodoros Before Long Break",
+        kind: SettingKind::Number { min: 1, max: 99 },
+        default: "4",
+        get: |config| config.pomodoro_cycles.to_string(),
+        set: |config, value| config.pomodoro_cycles = value.into(),
+    },
+    Setting {
+        name: "pomodoro_auto_continue",
+        label: "Auto-Continue Pomodoros",
+        kind: SettingKind::Bool,
+        default: "true",
+        get: |config| config.pomodoro_auto_continue.to_string(),
+        set: |config, value| config.pomodoro_auto_continue = value.into(),
+    },
+    Setting {
+        name: "mouse_support",
+        label: "Mouse Support",
+        kind: SettingKind::Bool,
+        default: "true",
+        get: |config| config.mouse_support.to_string(),
+        set: |config, value| config.mouse_support = value.into(),
+    },
+    Setting {
+        name: "taskwarrior_on_finish",
+        label: "Taskwarrior On Finish",
+        kind: SettingKind::Choice(taskwarrior_finishes),
+        default: "None",
+        get: |config| config.taskwarrior_on_finish.clone(),
+        set: |config, value| config.taskwarrior_on_finish = value.into(),
+    },
+    Setting {
+        name: "taskwarrior_command",
+        label: "Taskwarrior Command",
+        kind: SettingKind::Text,
+        default: "task",
+        get: |config| config.taskwarrior_command.clone(),
+        set: |config, value| config.taskwarrior_command = value.into(),
+    },
+    Setting {
+        name: "taskwarrior_duration_uda",
+        label: "Taskwarrior Duration UDA",
+        kind: SettingKind::Text,
+        default: "estimate",
+        get: |config| config.taskwarrior_duration_uda.clone(),
+        set: |config, value| config.taskwarrior_duration_uda = value.into(),
+    },
+    Setting {
+        name: "require_ack",
+        label: "Wait For Acknowledgement",
+        kind: SettingKind::Bool,
+        default: "false",
+        get: |config| config.require_ack.to_string(),
+        set: |config, value| config.require_ack = value.into(),
+    },
+    Setting {
+        name: "snooze_minutes",
+        label: "Snooze Time",
+        kind: SettingKind::Number { min: 1, max: 99 },
+        default: "5",
+        get: |config| config.snooze_minutes.to_string(),
+        set: |config, value| config.snooze_minutes = value.into(),
+    },
+    Setting {
+        name: "adjust_step",
+        label: "Adjust Step",
+        kind: SettingKind::Number { min: 1, max: 99 },
+        default: "5",
+        get: |config| config.adjust_step.to_string(),
+        set: |config, value| config.adjust_step = value.into(),
+    },
+    Setting {
+        name: "complete_on_checklist",
+        label: "Finish When Checklist Done",
+        kind: SettingKind::Bool,
+        default: "false",
+        get: |config| config.complete_on_checklist.to_string(),
+        set: |config, value| config.complete_on_checklist = value.into(),
+    },
+    Setting {
+        name: "background_color",
+        label: "Background Color",
+        kind: SettingKind::Color {
+            allow_default: true,
+        },
+        default: "Default",
+        get: |config| config.background_color.clone(),
+        set: |config, value| config.background_color = value.into(),
+    },
+    Setting {
+        name: "foreground_color",
+        label: "Foreground Color",
+        kind: SettingKind::Color {
+            allow_default: true,
+        },
+        default: "Default",
+        get: |config| config.foreground_color.clone(),
+        set: |config, value| config.foreground_color = value.into(),
+    },
+    Setting {
+        name: "theme",
+        label: "Theme",
+        kind: SettingKind::Choice(theme_names),
+        default: "Default",
+        get: |config| config.theme.clone(),
+        set: |config, value| config.theme = value.into(),
+    },
+    Setting {
+        name: "big_digits",
+        label: "Big Digits",
+        kind: SettingKind::Bool,
+        default: "false",
+        get: |config| config.big_digits.to_string(),
+        set: |config, value| config.big_digits = value.into(),
+    },
+    Setting {
+        name: "table_view",
+        label: "Table View",
+        kind: SettingKind::Bool,
+        default: "false",
+        get: |config| config.table_view.to_string(),
+        set: |config, value| config.table_view = value.into(),
+    },
+    Setting {
+        name: "auto_focus_mode",
+        label: "Auto Focus Mode",
+        kind: SettingKind::Bool,
+        default: "false",
+        get: |config| config.auto_focus_mode.to_string(),
+        set: |config, value| config.auto_focus_mode = value.into(),
+    },
+];
+
+/// The index of the setting with the name of its config.json field
+pub fn setting_index(name: &str) -> Option<usize> {
+    SETTINGS.iter().position(|setting| setting.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(name: &str) -> &'static Setting {
+        &SETTINGS[setting_index(name).unwrap()]
+    }
+
+    #[test]
+    fn defaults_are_valid_and_used_by_new_configurations() {
+        let config = Configuration::new();
+        for setting in &SETTINGS {
+            assert!(setting.check(setting.default).is_ok(), "{}", setting.name);
+            assert_eq!((setting.get)(&config), setting.default, "{}", setting.name);
+        }
+    }
+
+    #[test]
+    fn checks_numbers_against_their_range() {
+        let snooze = setting("snooze_minutes");
+        assert_eq!(snooze.check("1"), Ok(SettingValue::Number(1)));
+        assert_eq!(snooze.check(" 99 "), Ok(SettingValue::Number(99)));
+        assert_eq!(
+            snooze.check("0"),
+            Err("Invalid value '0' for Snooze Time, use a number from 1 to 99".to_string())
+        );
+        assert!(snooze.check("100").is_err());
+        assert!(snooze.check("-1").is_err());
+        assert!(snooze.check("five").is_err());
+    }
+
+    #[test]
+    fn empty_values_are_the_default() {
+        assert_eq!(
+            setting("pomodoro_time").check(""),
+            Ok(SettingValue::Number(25))
+        );
+        assert_eq!(
+            setting("darkmode").check("  "),
+            Ok(SettingValue::Bool(true))
+        );
+    }
+
+    #[test]
+    fn checks_the_other_kinds() {
+        assert_eq!(
+            setting("big_digits").check("false"),
+            Ok(SettingValue::Bool(false))
+        );
+        assert!(setting("big_digits").check("yes").is_err());
+        let action = setting("action_timeout");
+        assert_eq!(
+            action.check("Shutdown"),
+            Ok(SettingValue::Text("Shutdown".to_string()))
+        );
+        assert!(action.check("shutdown").is_err());
+        // only the background and foreground may follow the darkmode setting
+        assert!(setting("background_color").check("Default").is_ok());
+        assert!(setting("activecolor").check("Default").is_err());
+        assert!(setting("activecolor").check("#ff8700").is_ok());
+        assert!(setting("taskwarrior_command")
+            .check("task rc.confirmation=off")
+            .is_ok());
+    }
+
+    #[test]
+    fn cycles_numbers_within_their_range() {
+        let snooze = setting("snooze_minutes");
+        assert_eq!(snooze.cycle("5", true), "6");
+        assert_eq!(snooze.cycle("5", false), "4");
+        assert_eq!(snooze.cycle("99", true), "99");
+        assert_eq!(snooze.cycle("1", false), "1");
+        assert_eq!(snooze.cycle("500", false), "99");
+        assert_eq!(snooze.cycle("abc", true), "2");
+    }
+
+    #[test]
+    fn cycles_choices_and_colors_around() {
+        let action = setting("action_timeout");
+        assert_eq!(action.cycle("Shutdown", true), "None");
+        assert_eq!(action.cycle("None", false), "Shutdown");
+        assert_eq!(action.cycle("unknown", true), "None");
+        assert_eq!(setting("darkmode").cycle("true", true), "false");
+        assert_eq!(setting("activecolor").cycle("White", true), "Black");
+        assert_eq!(setting("background_color").cycle("White", true), "Default");
+        assert_eq!(setting("background_color").cycle("Default", true), "Black");
+        assert_eq!(setting("taskwarrior_command").cycle("task", true), "task");
+    }
+
+    #[test]
+    fn keeps_the_value_of_a_choice_without_names() {
+        let empty = Setting {
+            name: "empty",
+            label: "Empty",
+            kind: SettingKind::Choice(Vec::new),
+            default: "",
+            get: |_| String::new(),
+            set: |_, _| {},
+        };
+        assert_eq!(empty.cycle("value", true), "value");
+        assert_eq!(empty.cycle("", false), "");
+    }
+
+    #[test]
+    fn setters_store_the_checked_value() {
+        let mut config = Configuration::new();
+        let step = setting("adjust_step");
+        (step.set)(&mut config, step.check("10").unwrap());
+        assert_eq!(config.adjust_step, 10);
+        let snooze = setting("snooze_minutes");
+        (snooze.set)(&mut config, snooze.check("12").unwrap());
+        assert_eq!(config.snooze_minutes, 12);
+        let darkmode = setting("darkmode");
+        (darkmode.set)(&mut config, darkmode.check("false").unwrap());
+        assert!(!config.darkmode);
+    }
+}
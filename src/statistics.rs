@@ -0,0 +1,284 @@
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::history::{HistoryEntry, Outcome};
+
+/// Aggregated focus time of the history entries in a date range
+pub struct Statistics {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Seconds the timers counted down, see `HistoryEntry::focused_secs`
+    pub total_focused_secs: u64,
+    /// Time the timers ran over before the user moved on, breaks included
+    pub total_overtime_secs: u64,
+    pub focused_per_day: Vec<(NaiveDate, u64)>,
+    pub focused_per_week: Vec<(String, u64)>,
+    pub focused_per_type: Vec<(String, u64)>,
+    /// Entries with several tags count for each of them
+    pub focused_per_tag: Vec<(String, u64)>,
+    pub pomodoros_completed: u64,
+    pub pomodoros_interrupted: u64,
+    pub current_streak: u64,
+    pub longest_streak: u64,
+}
+
+/// Breaks don't count as focused time
+fn is_focus(entry: &HistoryEntry) -> bool {
+    entry.timer_type.as_deref() != Some("break")
+}
+
+impl Statistics {
+    pub fn from_history(entries: &[HistoryEntry], from: NaiveDate, to: NaiveDate) -> Self {
+        let mut per_day: BTreeMap<NaiveDate, u64> = BTreeMap::new();
+        let mut day = from;
+        while day <= to {
+            per_day.insert(day, 0);
+            day += Duration::days(1);
+        }
+        let mut per_week: BTreeMap<(i32, u32), u64> = BTreeMap::new();
+        let mut per_type: BTreeMap<String, u64> = BTreeMap::new();
+        let mut per_tag: BTreeMap<String, u64> = BTreeMap::new();
+        let mut pomodoros_completed = 0;
+        let mut pomodoros_interrupted = 0;
+        let in_range = |entry: &&HistoryEntry| {
+            let day = entry.ended_at.date_naive();
+            day >= from && day <= to
+        };
+        let total_overtime_secs = entries
+            .iter()
+            .filter(in_range)
+            .map(|e| e.overtime_secs)
+            .sum();
+
+        for entry in entries.iter().filter(in_range).filter(|e| is_focus(e)) {
+            let day = entry.ended_at.date_naive();
+            // the ticked time, not the time between start and end, which would count the
+            // time the app was closed
+            let secs = entry.focused_secs();
+            *per_day.entry(day).or_default() += secs;
+            let week = day.iso_week();
+            *per_week.entry((week.year(), week.week())).or_default() += secs;
+            let timer_type = entry.timer_type.clone().unwrap_or("none".to_string());
+            *per_type.entry(timer_type).or_default() += secs;
+            if entry.tags.is_empty() {
+                *per_tag.entry("none".to_string()).or_default() += secs;
+            }
+            for tag in &entry.tags {
+                *per_tag.entry(format!("#{}", tag)).or_default() += secs;
+            }
+            // only the work timers of a Pomodoro cycle are pomodoros
+            if entry.pomodoro_cycle.is_some() {
+                match entry.outcome {
+                    Outcome::Completed => pomodoros_completed += 1 + entry.repeats,
+                    Outcome::Interrupted => pomodoros_interrupted += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        let (current_streak, longest_streak) = streaks(entries, Local::now().date_naive());
+        let mut focused_per_type: Vec<(String, u64)> = per_type.into_iter().collect();
+        focused_per_type.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+        let mut focused_per_tag: Vec<(String, u64)> = per_tag.into_iter().collect();
+        focused_per_tag.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+
+        Self {
+            from,
+            to,
+            total_focused_secs: per_day.values().sum(),
+            total_overtime_secs,
+            focused_per_day: per_day.into_iter().collect(),
+            focused_per_week: per_week
+                .into_iter()
+                .map(|((year, week), secs)| (format!("{}-W{:02}", year, week), secs))
+                .collect(),
+            focused_per_type,
+            focused_per_tag,
+            pomodoros_completed,
+            pomodoros_interrupted,
+            current_streak,
+            longest_streak,
+        }
+    }
+}
+
+/// Current and longest run of consecutive days with at least one completed focus timer.
+/// The current streak is still alive if the last such day was yesterday.
+fn streaks(entries: &[HistoryEntry], today: NaiveDate) -> (u64, u64) {
+    let days: BTreeSet<NaiveDate> = entries
+        .iter()
+        .filter(|e| e.outcome == Outcome::Completed && is_focus(e))
+        .map(|e| e.ended_at.date_naive())
+        .collect();
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &day in &days {
+        run = match previous {
+            Some(p) if p + Duration::days(1) == day => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    let mut current = 0;
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    while days.contains(&day) {
+        current += 1;
+        day -= Duration::days(1);
+    }
+    (current, longest)
+}
+
+/// Formats seconds as e.g. "2h 05m"
+pub fn format_duration(secs: u64) -> String {
+    format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone};
+
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    fn entry(
+        timer_type: &str,
+        outcome: Outcome,
+        ended_at: DateTime<Local>,
+        secs: u64,
+    ) -> HistoryEntry {
+        HistoryEntry {
+            description: "Report".to_string(),
+            timer_type: Some(timer_type.to_string()),
+            tags: vec!["clientA".to_string()],
+            outcome,
+            planned_secs: secs,
+            started_at: Some(ended_at - Duration::seconds(secs as i64)),
+            ended_at,
+            pause_count: 0,
+            paused_secs: 0,
+            repeats: 0,
+            ticked_secs: secs,
+            pomodoro_cycle: Some(1),
+            snoozes: 0,
+            overtime_secs: 0,
+        }
+    }
+
+    #[test]
+    fn counts_entries_on_the_day_they_ended() {
+        // a timer that ran over midnight counts for the next day, 2026-03-01 is a Sunday
+        let entries = [
+            entry("focus", Outcome::Completed, time(1, 23, 50), 1500),
+            entry("focus", Outcome::Completed, time(2, 0, 10), 1500),
+            entry("break", Outcome::Completed, time(2, 0, 20), 300),
+        ];
+        let stats = Statistics::from_history(&entries, date(1), date(2));
+        assert_eq!(
+            stats.focused_per_day,
+            vec![(date(1), 1500), (date(2), 1500)]
+        );
+        assert_eq!(
+            stats.focused_per_week,
+            vec![
+                ("2026-W09".to_string(), 1500),
+                ("2026-W10".to_string(), 1500)
+            ]
+        );
+        assert_eq!(stats.total_focused_secs, 3000);
+        assert_eq!(stats.pomodoros_completed, 2);
+
+        let stats = Statistics::from_history(&entries, date(2), date(2));
+        assert_eq!(stats.focused_per_day, vec![(date(2), 1500)]);
+    }
+
+    #[test]
+    fn groups_focus_time_per_type_and_tag() {
+        let mut untagged = entry("coding", Outcome::Skipped, time(1, 12, 0), 600);
+        untagged.tags.clear();
+        let mut two_tags = entry("focus", Outcome::Interrupted, time(1, 11, 0), 900);
+        two_tags.tags.push("urgent".to_string());
+        let entries = [
+            entry("focus", Outcome::Completed, time(1, 10, 0), 1500),
+            two_tags,
+            untagged,
+        ];
+        let stats = Statistics::from_history(&entries, date(1), date(1));
+        assert_eq!(
+            stats.focused_per_type,
+            vec![("focus".to_string(), 2400), ("coding".to_string(), 600)]
+        );
+        // a timer with several tags counts for each of them
+        assert_eq!(
+            stats.focused_per_tag,
+            vec![
+                ("#clientA".to_string(), 2400),
+                ("#urgent".to_string(), 900),
+                ("none".to_string(), 600)
+            ]
+        );
+        assert_eq!(stats.pomodoros_completed, 1);
+        assert_eq!(stats.pomodoros_interrupted, 1);
+    }
+
+    #[test]
+    fn counts_only_timers_of_a_pomodoro_cycle_as_pomodoros() {
+        let mut plain = entry("focus", Outcome::Completed, time(1, 10, 0), 1500);
+        plain.pomodoro_cycle = None;
+        let mut removed = entry("coding", Outcome::Interrupted, time(1, 11, 0), 900);
+        removed.pomodoro_cycle = None;
+        let mut repeated = entry("coding", Outcome::Completed, time(1, 12, 0), 3000);
+        repeated.repeats = 1;
+        let entries = [plain, removed, repeated];
+        let stats = Statistics::from_history(&entries, date(1), date(1));
+        assert_eq!(stats.pomodoros_completed, 2);
+        assert_eq!(stats.pomodoros_interrupted, 0);
+        assert_eq!(stats.total_focused_secs, 5400);
+    }
+
+    #[test]
+    fn keeps_overtime_apart_from_the_focused_time() {
+        let mut focus = entry("focus", Outcome::Completed, time(1, 10, 0), 1500);
+        focus.overtime_secs = 120;
+        let mut pause = entry("break", Outcome::Completed, time(1, 10, 10), 300);
+        pause.overtime_secs = 60;
+        let mut earlier = entry("focus", Outcome::Completed, time(2, 9, 0), 1500);
+        earlier.overtime_secs = 600;
+        let stats = Statistics::from_history(&[focus, pause, earlier], date(1), date(1));
+        assert_eq!(stats.total_overtime_secs, 180);
+        assert_eq!(stats.total_focused_secs, 1500);
+    }
+
+    #[test]
+    fn streaks_count_consecutive_days_with_completed_focus() {
+        let entries = [
+            entry("focus", Outcome::Completed, time(1, 23, 59), 1500),
+            entry("focus", Outcome::Completed, time(2, 0, 1), 1500),
+            entry("focus", Outcome::Completed, time(3, 9, 0), 1500),
+            // skipped timers and breaks don't keep a streak alive
+            entry("focus", Outcome::Skipped, time(4, 9, 0), 1500),
+            entry("break", Outcome::Completed, time(5, 9, 0), 300),
+            entry("focus", Outcome::Completed, time(6, 9, 0), 1500),
+        ];
+        assert_eq!(streaks(&entries, date(6)), (1, 3));
+        // the streak is still alive on the next day
+        assert_eq!(streaks(&entries, date(7)), (1, 3));
+        assert_eq!(streaks(&entries, date(8)), (0, 3));
+        assert_eq!(streaks(&entries, date(4)), (3, 3));
+    }
+}
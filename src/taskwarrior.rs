@@ -0,0 +1,466 @@
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::str::FromStr;
+
+use crate::configuration::Configuration;
+use crate::timer::Timer;
+use crate::ui_states::TaskwarriorFinish;
+
+/// A task as printed by `task export`, user defined attributes end up in `udas`
+#[derive(Deserialize)]
+pub struct TaskwarriorTask {
+    pub uuid: String,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(flatten)]
+    pub udas: HashMap<String, serde_json::Value>,
+}
+
+/// The configured task command, e.g. `task`. The command may contain arguments itself, like
+/// `task rc.confirmation=off`.
+fn task_command(config: &Configuration) -> Result<Command, String> {
+    let mut command_parts = config.taskwarrior_command.split_whitespace();
+    let program = command_parts
+        .next()
+        .ok_or("no taskwarrior command configured")?;
+    let mut command = Command::new(program);
+    command.args(command_parts);
+    Ok(command)
+}
+
+/// The message for a task command that exited with an error
+fn failed_message(task_command: &str, args: &[&str], status: ExitStatus, stderr: &str) -> String {
+    format!(
+        "'{} {}' failed: {}",
+        task_command,
+        args.join(" "),
+        if stderr.trim().is_empty() {
+            status.to_string()
+        } else {
+            stderr.trim().to_string()
+        }
+    )
+}
+
+/// Runs the configured task command with the given arguments and waits for it, e.g.
+/// `task status:pending export`
+pub fn run_task_command(config: &Configuration, args: &[&str]) -> Result<String, String> {
+    let output = task_command(config)?
+        .args(args)
+        .output()
+        .map_err(|e| format!("running '{}' failed: {}", config.taskwarrior_command, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(failed_message(
+            &config.taskwarrior_command,
+            args,
+            output.status,
+            &stderr,
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Pending tasks matching a Taskwarrior filter expression such as `project:work +review`
+pub fn export_pending_tasks(
+    config: &Configuration,
+    filter: &[&str],
+) -> Result<Vec<TaskwarriorTask>, String> {
+    let mut args = filter.to_vec();
+    args.extend(["status:pending", "export"]);
+    let output = run_task_command(config, &args)?;
+    serde_json::from_str(&output).map_err(|e| format!("invalid taskwarrior export: {}", e))
+}
+
+/// Parses a duration UDA in minutes: plain numbers are minutes, Taskwarrior's duration type is
+/// exported in ISO 8601 form like `PT1H30M`
+pub fn parse_duration_minutes(value: &serde_json::Value) -> Option<u64> {
+    let number = value
+        .as_f64()
+        .or_else(|| value.as_str()?.trim().parse().ok());
+    let minutes = match number {
+        // the cast saturates, negative numbers become 0
+        Some(minutes) => minutes.round() as u64,
+        None => iso_8601_minutes(value.as_str()?.trim())?,
+    };
+    // an empty or negative duration falls back to the Pomodoro time, the timer counts seconds
+    (minutes > 0 && minutes.checked_mul(60).is_some()).then_some(minutes)
+}
+
+/// Minutes of a duration like `PT1H30M`, seconds are rounded up
+fn iso_8601_minutes(text: &str) -> Option<u64> {
+    let time = text.strip_prefix("PT")?;
+    let mut secs: u64 = 0;
+    let mut number = String::new();
+    for c in time.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'H' | 'M' | 'S' => {
+                let n: u64 = number.parse().ok()?;
+                let unit = match c {
+                    'H' => 3600,
+                    'M' => 60,
+                    _ => 1,
+                };
+                secs = secs.checked_add(n.checked_mul(unit)?)?;
+                number.clear();
+            }
+            _ => return None,
+        }
+    }
+    Some(secs.div_ceil(60))
+}
+
+impl TaskwarriorTask {
+    pub fn to_timer(&self, config: &Configuration) -> Timer {
+        let minutes = self
+            .udas
+            .get(&config.taskwarrior_duration_uda)
+            .and_then(parse_duration_minutes)
+            .unwrap_or(config.pomodoro_time);
+        let timer_type = self
+            .tags
+            .iter()
+            .map(|tag| tag.to_lowercase())
+            .find(|tag| config.timer_colors.contains_key(tag));
+        let mut timer = Timer::new(self.description.clone(), minutes * 60, true, timer_type);
+        timer.taskwarrior_uuid = Some(self.uuid.clone());
+        timer.tags = self.tags.clone();
+        timer
+    }
+}
+
+/// Adds a timer for every pending task matching the filter that isn't linked to a timer yet
+/// and returns how many were added
+pub fn import_tasks(config: &mut Configuration, filter: &[&str]) -> Result<usize, String> {
+    let tasks = export_pending_tasks(config, filter)?;
+    let mut imported = 0;
+    for task in tasks {
+        let already_linked = config
+            .timers
+            .iter()
+            .any(|t| t.taskwarrior_uuid.as_deref() == Some(task.uuid.as_str()));
+        if !already_linked {
+            let timer = task.to_timer(config);
+            config.add_timer_to_config(timer, false);
+            imported += 1;
+        }
+    }
+    Ok(imported)
+}
+
+/// A task command of a timer that runs in the background
+struct TaskJob {
+    uuid: String,
+    args: Vec<String>,
+    /// Whether the task is started once a start or stop succeeded, None for other commands
+    started: Option<bool>,
+}
+
+/// The task commands of the timers, run one after the other in the background so that the
+/// ticks don't wait for Taskwarrior
+#[derive(Default)]
+pub struct TaskQueue {
+    running: Option<(Child, TaskJob)>,
+    waiting: VecDeque<TaskJob>,
+}
+
+impl TaskQueue {
+    /// Whether the task is started once the queued commands are done
+    fn started_after(&self, uuid: &str) -> Option<bool> {
+        self.waiting
+            .iter()
+            .rev()
+            .chain(self.running.as_ref().map(|(_, job)| job))
+            .filter(|job| job.uuid == uuid)
+            .find_map(|job| job.started)
+    }
+}
+
+fn queue_task_command(
+    config: &mut Configuration,
+    uuid: &str,
+    args: &[&str],
+    started: Option<bool>,
+) {
+    let mut job_args = vec![uuid.to_string()];
+    job_args.extend(args.iter().map(|arg| arg.to_string()));
+    config.task_queue.waiting.push_back(TaskJob {
+        uuid: uuid.to_string(),
+        args: job_args,
+        started,
+    });
+    run_task_queue(config);
+}
+
+/// Takes the result of the running task command if it exited and starts the next one.
+/// Failures are shown in the status line.
+pub fn run_task_queue(config: &mut Configuration) {
+    loop {
+        if let Some((child, job)) = &mut config.task_queue.running {
+            let result = match child.try_wait() {
+                Ok(None) => return,
+                Ok(Some(status)) if status.success() => Ok(()),
+                Ok(Some(status)) => {
+                    let mut stderr = String::new();
+                    if let Some(output) = &mut child.stderr {
+                        let _ = output.read_to_string(&mut stderr);
+                    }
+                    let args: Vec<&str> = job.args.iter().map(String::as_str).collect();
+                    Err(failed_message(
+                        &config.taskwarrior_command,
+                        &args,
+                        status,
+                        &stderr,
+                    ))
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            let Some((_, job)) = config.task_queue.running.take() else {
+                return;
+            };
+            finish_job(config, job, result);
+        }
+        let Some(job) = config.task_queue.waiting.pop_front() else {
+            return;
+        };
+        let child = task_command(config).and_then(|mut command| {
+            command
+                .args(&job.args)
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .spawn()
+                .map_err(|e| format!("running '{}' failed: {}", config.taskwarrior_command, e))
+        });
+        match child {
+            Ok(child) => config.task_queue.running = Some((child, job)),
+            Err(e) => finish_job(config, job, Err(e)),
+        }
+    }
+}
+
+/// Waits for the queued task commands, e.g. before the app quits
+pub fn wait_for_task_queue(config: &mut Configuration) {
+    while let Some((child, _)) = &mut config.task_queue.running {
+        let _ = child.wait();
+        run_task_queue(config);
+    }
+}
+
+/// Stores the state of a task after a start or stop and reports a failure. A start or stop that
+/// failed isn't tried again until the timer changes its state.
+fn finish_job(config: &mut Configuration, job: TaskJob, result: Result<(), String>) {
+    let timer = config
+        .timers
+        .iter_mut()
+        .find(|t| t.taskwarrior_uuid.as_deref() == Some(job.uuid.as_str()));
+    match (result, job.started, timer) {
+        (Ok(()), Some(started), Some(timer)) => timer.taskwarrior_started = started,
+        (Err(e), started, timer) => {
+            if let (Some(_), Some(timer)) = (started, timer) {
+                timer.taskwarrior_failed = true;
+            }
+            config.status_message = Some(format!("Taskwarrior: {}", e));
+        }
+        _ => {}
+    }
+}
+
+/// Starts or stops the task linked to a timer so that it matches whether the timer is running.
+/// The command runs in the background, see `run_task_queue`.
+pub fn sync_task_state(config: &mut Configuration, index: usize, running: bool) {
+    let timer = &config.timers[index];
+    let Some(uuid) = timer.taskwarrior_uuid.clone() else {
+        return;
+    };
+    let started = config
+        .task_queue
+        .started_after(&uuid)
+        .unwrap_or(timer.taskwarrior_started);
+    if started == running {
+        config.timers[index].taskwarrior_failed = false;
+        return;
+    }
+    if timer.taskwarrior_failed {
+        return;
+    }
+    let command = if running { "start" } else { "stop" };
+    queue_task_command(config, &uuid, &[command], Some(running));
+}
+
+/// Stops the task linked to a finished timer and marks it done or annotates the time spent,
+/// depending on `taskwarrior_on_finish`
+pub fn finish_task(config: &mut Configuration, index: usize) {
+    sync_task_state(config, index, false);
+    let timer = &config.timers[index];
+    let Some(uuid) = timer.taskwarrior_uuid.clone() else {
+        return;
+    };
+    // the time the timer counted down, not its planned time
+    let spent_minutes = timer.ticked_secs / 60;
+    match TaskwarriorFinish::from_str(&config.taskwarrior_on_finish).unwrap() {
+        TaskwarriorFinish::None => {}
+        TaskwarriorFinish::Done => queue_task_command(config, &uuid, &["done"], None),
+        TaskwarriorFinish::Annotate => queue_task_command(
+            config,
+            &uuid,
+            &[
+                "annotate",
+                &format!("Spent {} min in multitimer-tui", spent_minutes),
+            ],
+            None,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn reads_numbers_as_minutes() {
+        assert_eq!(parse_duration_minutes(&json!(25)), Some(25));
+        assert_eq!(parse_duration_minutes(&json!(12.6)), Some(13));
+        assert_eq!(parse_duration_minutes(&json!(" 30 ")), Some(30));
+    }
+
+    #[test]
+    fn reads_iso_8601_durations() {
+        assert_eq!(parse_duration_minutes(&json!("PT1H30M")), Some(90));
+        assert_eq!(parse_duration_minutes(&json!("PT2H")), Some(120));
+        // seconds are rounded up to whole minutes
+        assert_eq!(parse_duration_minutes(&json!("PT45S")), Some(1));
+        assert_eq!(parse_duration_minutes(&json!("PT25M30S")), Some(26));
+    }
+
+    #[test]
+    fn rejects_other_values() {
+        assert_eq!(parse_duration_minutes(&json!("P1D")), None);
+        assert_eq!(parse_duration_minutes(&json!("PT1X")), None);
+        assert_eq!(parse_duration_minutes(&json!("PTM")), None);
+        assert_eq!(parse_duration_minutes(&json!("soon")), None);
+        assert_eq!(parse_duration_minutes(&json!(true)), None);
+        // empty and negative durations
+        assert_eq!(parse_duration_minutes(&json!(-5)), None);
+        assert_eq!(parse_duration_minutes(&json!("-5")), None);
+        assert_eq!(parse_duration_minutes(&json!(0)), None);
+        assert_eq!(parse_duration_minutes(&json!(0.4)), None);
+        assert_eq!(parse_duration_minutes(&json!("PT0M")), None);
+        // durations whose seconds don't fit
+        assert_eq!(parse_duration_minutes(&json!(1e30)), None);
+        assert_eq!(parse_duration_minutes(&json!("inf")), None);
+        assert_eq!(
+            parse_duration_minutes(&json!("PT18446744073709551615H")),
+            None
+        );
+        assert_eq!(
+            parse_duration_minutes(&json!("PT5124095576030431H16S")),
+            None
+        );
+    }
+
+    /// A config whose task command is a shell script that logs its arguments to `calls` in
+    /// its directory and then runs `script`, linked to one timer
+    fn config_with_stub(name: &str, script: &str) -> (Configuration<'static>, PathBuf) {
+        let dir =
+            std::env::temp_dir().join(format!("multitimer-tui-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let _ = std::fs::remove_file(dir.join("calls"));
+        let stub = dir.join("task.sh");
+        let log = format!("echo \"$*\" >> '{}'\n", dir.join("calls").display());
+        std::fs::write(&stub, log + script).unwrap();
+        let mut config = Configuration::new();
+        config.taskwarrior_command = format!("sh {}", stub.display());
+        let mut timer = Timer::new("Report".to_string(), 180, true, None);
+        timer.taskwarrior_uuid = Some("u1".to_string());
+        config.timers.push(timer);
+        (config, dir)
+    }
+
+    fn calls(dir: &Path) -> Vec<String> {
+        let calls = std::fs::read_to_string(dir.join("calls")).unwrap_or_default();
+        calls.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn starts_stops_and_finishes_the_linked_task() {
+        let (mut config, dir) = config_with_stub("finish", "");
+        sync_task_state(&mut config, 0, true);
+        // the task counts as started once the start is queued
+        sync_task_state(&mut config, 0, true);
+        wait_for_task_queue(&mut config);
+        assert!(config.timers[0].taskwarrior_started);
+
+        config.timers[0].ticked_secs = 150;
+        config.taskwarrior_on_finish = "Annotate".to_string();
+        finish_task(&mut config, 0);
+        config.taskwarrior_on_finish = "Done".to_string();
+        finish_task(&mut config, 0);
+        wait_for_task_queue(&mut config);
+
+        assert!(!config.timers[0].taskwarrior_started);
+        assert_eq!(config.status_message, None);
+        assert_eq!(
+            calls(&dir),
+            [
+                "u1 start",
+                "u1 stop",
+                "u1 annotate Spent 2 min in multitimer-tui",
+                "u1 done"
+            ]
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reports_a_failed_task_command_in_the_status_line() {
+        let (mut config, dir) = config_with_stub("failure", "echo 'No such task.' >&2\nexit 1\n");
+        sync_task_state(&mut config, 0, true);
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while config.status_message.is_none() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+            run_task_queue(&mut config);
+        }
+
+        let message = config.status_message.clone().unwrap_or_default();
+        assert!(message.starts_with("Taskwarrior: 'sh "), "{}", message);
+        assert!(
+            message.ends_with("task.sh u1 start' failed: No such task."),
+            "{}",
+            message
+        );
+        assert!(!config.timers[0].taskwarrior_started);
+        // the start isn't tried again on every tick
+        sync_task_state(&mut config, 0, true);
+        wait_for_task_queue(&mut config);
+        assert_eq!(calls(&dir), ["u1 start"]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn uses_the_duration_uda_or_the_pomodoro_time() {
+        let config = Configuration::new();
+        let task = |udas: serde_json::Value| -> TaskwarriorTask {
+            let mut value = json!({"uuid": "u1", "description": "Report", "tags": ["Focus"]});
+            value
+                .as_object_mut()
+                .unwrap()
+                .extend(udas.as_object().unwrap().clone());
+            serde_json::from_value(value).unwrap()
+        };
+        let timer = task(json!({"estimate": "PT1H"})).to_timer(&config);
+        assert_eq!(timer.initial_time, 3600);
+        assert_eq!(timer.timer_type.as_deref(), Some("focus"));
+        assert_eq!(timer.taskwarrior_uuid.as_deref(), Some("u1"));
+        let timer = task(json!({"estimate": "later"})).to_timer(&config);
+        assert_eq!(timer.initial_time, config.pomodoro_time * 60);
+        let timer = task(json!({"estimate": -5})).to_timer(&config);
+        assert_eq!(timer.initial_time, config.pomodoro_time * 60);
+    }
+}
@@ -0,0 +1,278 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::color::AcceptedColors;
+
+/// Colors of the styled elements, as in config.json: a color name, a palette index (0-255),
+/// a hex value (#rrggbb) or "Active" for the active color of the Config tab. Missing entries
+/// in a theme file are taken from the dark theme.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Theme {
+    pub background: String,
+    pub foreground: String,
+    /// Titles of the tabs that aren't selected
+    pub tabs: String,
+    /// Background of the selected tab
+    pub tab_highlight: String,
+    pub border: String,
+    /// Gauge of the running timer, the color of the timer if not set
+    pub gauge: Option<String>,
+    pub inactive: String,
+    /// Running timer that is paused, the color of the timer if not set
+    pub paused: Option<String>,
+    /// Timers in overtime and warnings
+    pub finished: String,
+    pub popup: String,
+    pub input: String,
+}
+
+/// The colors of a theme ready to be drawn
+pub struct Palette {
+    pub background: Color,
+    pub foreground: Color,
+    pub tabs: Color,
+    pub tab_highlight: Color,
+    pub border: Color,
+    pub gauge: Option<Color>,
+    pub inactive: Color,
+    pub paused: Option<Color>,
+    pub finished: Color,
+    pub popup: Color,
+    pub input: Color,
+}
+
+pub const BUILTIN_THEMES: [&str; 4] = ["dark", "light", "solarized", "high-contrast"];
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            background: "Black".to_string(),
+            foreground: "White".to_string(),
+            tabs: "Gray".to_string(),
+            tab_highlight: "Active".to_string(),
+            border: "White".to_string(),
+            gauge: None,
+            inactive: "DarkGray".to_string(),
+            paused: None,
+            finished: "LightRed".to_string(),
+            popup: "LightRed".to_string(),
+            input: "Active".to_string(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            background: "White".to_string(),
+            foreground: "Black".to_string(),
+            tabs: "DarkGray".to_string(),
+            tab_highlight: "Active".to_string(),
+            border: "Black".to_string(),
+            gauge: None,
+            inactive: "Gray".to_string(),
+            paused: None,
+            finished: "Red".to_string(),
+            popup: "Red".to_string(),
+            input: "Active".to_string(),
+        }
+    }
+
+    /// Solarized dark, see https://ethanschoonover.com/solarized/
+    pub fn solarized() -> Self {
+        Self {
+            background: "#002b36".to_string(),
+            foreground: "#839496".to_string(),
+            tabs: "#586e75".to_string(),
+            tab_highlight: "#268bd2".to_string(),
+            border: "#586e75".to_string(),
+            gauge: None,
+            inactive: "#586e75".to_string(),
+            paused: Some("#b58900".to_string()),
+            finished: "#dc322f".to_string(),
+            popup: "#cb4b16".to_string(),
+            input: "#268bd2".to_string(),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            background: "Black".to_string(),
+            foreground: "White".to_string(),
+            tabs: "White".to_string(),
+            tab_highlight: "Yellow".to_string(),
+            border: "White".to_string(),
+            gauge: Some("Yellow".to_string()),
+            inactive: "Gray".to_string(),
+            paused: Some("LightYellow".to_string()),
+            finished: "LightRed".to_string(),
+            popup: "White".to_string(),
+            input: "Yellow".to_string(),
+        }
+    }
+
+    /// The first entry that isn't "Active" or a color `AcceptedColors::parse` accepts, with its
+    /// key as in the theme file
+    fn invalid_color(&self) -> Option<(&'static str, &str)> {
+        [
+            ("background", Some(&self.background)),
+            ("foreground", Some(&self.foreground)),
+            ("tabs", Some(&self.tabs)),
+            ("tab_highlight", Some(&self.tab_highlight)),
+            ("border", Some(&self.border)),
+            ("gauge", self.gauge.as_ref()),
+            ("inactive", Some(&self.inactive)),
+            ("paused", self.paused.as_ref()),
+            ("finished", Some(&self.finished)),
+            ("popup", Some(&self.popup)),
+            ("input", Some(&self.input)),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?.as_str())))
+        .find(|(_, value)| {
+            !value.eq_ignore_ascii_case("active") && AcceptedColors::parse(value).is_none()
+        })
+    }
+
+    /// Resolves the colors, "Active" becomes the given active color
+    pub fn palette(&self, active: Color) -> Palette {
+        let color = |value: &str| {
+            if value.eq_ignore_ascii_case("active") {
+                active
+            } else {
+                AcceptedColors::from_str(value).unwrap().to_color()
+            }
+        };
+        Palette {
+            background: color(&self.background),
+            foreground: color(&self.foreground),
+            tabs: color(&self.tabs),
+            tab_highlight: color(&self.tab_highlight),
+            border: color(&self.border),
+            gauge: self.gauge.as_deref().map(color),
+            inactive: color(&self.inactive),
+            paused: self.paused.as_deref().map(color),
+            finished: color(&self.finished),
+            popup: color(&self.popup),
+            input: color(&self.input),
+        }
+    }
+}
+
+/// Built-in themes followed by the user themes in the themes directory. "Default" is the dark
+/// or the light theme, depending on the darkmode setting.
+pub fn theme_names() -> Vec<String> {
+    let mut names = vec!["Default".to_string()];
+    names.extend(BUILTIN_THEMES.iter().map(|name| name.to_string()));
+    let mut user_themes: Vec<String> = std::fs::read_dir("themes")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    if path.extension()? != "json" {
+                        return None;
+                    }
+                    Some(path.file_stem()?.to_str()?.to_string())
+                })
+                .filter(|name| !names.contains(name))
+                .collect()
+        })
+        .unwrap_or_default();
+    user_themes.sort();
+    names.extend(user_themes);
+    names
+}
+
+/// Loads a built-in theme or themes/<name>.json, a theme file with a color that can't be read
+/// is rejected
+pub fn load_theme(name: &str, darkmode: bool) -> Result<Theme, String> {
+    match name {
+        "Default" if darkmode => Ok(Theme::dark()),
+        "Default" => Ok(Theme::light()),
+        "dark" => Ok(Theme::dark()),
+        "light" => Ok(Theme::light()),
+        "solarized" => Ok(Theme::solarized()),
+        "high-contrast" => Ok(Theme::high_contrast()),
+        _ => read_theme_file(&Path::new("themes").join(format!("{}.json", name))),
+    }
+}
+
+fn read_theme_file(path: &Path) -> Result<Theme, String> {
+    let data = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let theme: Theme =
+        serde_json::from_str(&data).map_err(|e| format!("{}: {}", path.display(), e))?;
+    match theme.invalid_color() {
+        Some((key, value)) => Err(format!(
+            "{}: invalid color '{}' for {}, use Active, {}",
+            path.display(),
+            value,
+            key,
+            AcceptedColors::names()
+        )),
+        None => Ok(theme),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme_file(name: &str, json: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("multitimer-theme-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.json", name));
+        std::fs::write(&path, json).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_the_built_in_themes() {
+        for name in BUILTIN_THEMES {
+            assert!(load_theme(name, true).is_ok(), "{}", name);
+        }
+        assert_eq!(load_theme("Default", true).unwrap().background, "Black");
+        assert_eq!(load_theme("Default", false).unwrap().background, "White");
+        assert!(load_theme("no-such-theme", true).is_err());
+    }
+
+    #[test]
+    fn takes_missing_entries_from_the_dark_theme() {
+        let path = theme_file("partial", r##"{"background": "#102030", "gauge": "208"}"##);
+        let theme = read_theme_file(&path).unwrap();
+        assert_eq!(theme.background, "#102030");
+        assert_eq!(theme.gauge.as_deref(), Some("208"));
+        assert_eq!(theme.foreground, Theme::dark().foreground);
+        assert_eq!(theme.paused, None);
+    }
+
+    #[test]
+    fn names_the_entry_with_a_color_that_cant_be_read() {
+        let path = theme_file("invalid", r#"{"border": "Purple"}"#);
+        let error = read_theme_file(&path).err().unwrap();
+        assert!(
+            error.contains("invalid color 'Purple' for border"),
+            "{}",
+            error
+        );
+
+        let path = theme_file("broken", r#"{"border": 3"#);
+        assert!(read_theme_file(&path).is_err());
+    }
+
+    #[test]
+    fn resolves_active_to_the_active_color() {
+        let palette = Theme::dark().palette(Color::Cyan);
+        assert_eq!(palette.tab_highlight, Color::Cyan);
+        assert_eq!(palette.input, Color::Cyan);
+        assert_eq!(palette.background, Color::Black);
+        assert_eq!(palette.gauge, None);
+    }
+}
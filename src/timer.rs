@@ -1,6 +1,15 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
-use std::process::Command;
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::OnceLock;
+
+/// An item of the checklist attached to a timer
+#[derive(Serialize, Deserialize)]
+pub struct Subtask {
+    pub description: String,
+    pub done: bool,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct Timer {
@@ -18,6 +27,92 @@ pub struct Timer {
     pub action_info: String,
     pub timer_type: Option<String>,
     pub repeat_times: u64,
+    #[serde(default)]
+    pub paused: bool,
+    #[serde(skip_serializing, skip_deserializing)]
+    pub marked: bool,
+    #[serde(default)]
+    pub started_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub pause_count: u64,
+    #[serde(default)]
+    pub paused_secs: u64,
+    /// Seconds the timer counted down, over all its repeats
+    #[serde(default)]
+    pub ticked_secs: u64,
+    #[serde(default)]
+    pub repeats_done: u64,
+    #[serde(default)]
+    pub taskwarrior_uuid: Option<String>,
+    #[serde(default)]
+    pub taskwarrior_started: bool,
+    /// A start or stop of the linked task failed, it is tried again when the timer is paused
+    /// or resumed
+    #[serde(skip_serializing, skip_deserializing)]
+    pub taskwarrior_failed: bool,
+    #[serde(default)]
+    pub pomodoro_cycle: Option<u64>,
+    #[serde(default)]
+    pub require_ack: bool,
+    #[serde(default)]
+    pub awaiting_ack: bool,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub snoozes: u64,
+    /// Finished, but the user hasn't moved on yet
+    #[serde(default)]
+    pub in_overtime: bool,
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+    /// Tags without the leading #, e.g. "clientA"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Whether the checklist is shown below the timer
+    #[serde(skip_serializing, skip_deserializing)]
+    pub expanded: bool,
+    /// Notification with a snooze button that is still open
+    #[serde(skip_serializing, skip_deserializing)]
+    pub notification: Option<Child>,
+}
+
+/// Whether notify-send can show action buttons and wait for them (libnotify 0.7.9 and newer)
+fn notify_send_supports_actions() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        Command::new("notify-send")
+            .arg("--help")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("--action"))
+            .unwrap_or(false)
+    })
+}
+
+/// Splits the `#tag` words off a text, returning the remaining text and the tags
+pub fn split_tags(text: &str) -> (String, Vec<String>) {
+    let mut words = Vec::new();
+    let mut tags = Vec::new();
+    for word in text.split_whitespace() {
+        match word.strip_prefix('#') {
+            Some(tag) if !tag.is_empty() => {
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_string());
+                }
+            }
+            _ => words.push(word),
+        }
+    }
+    (words.join(" "), tags)
+}
+
+/// Formats seconds as HH:MM:SS
+pub fn format_clock(secs: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
 }
 
 impl Timer {
@@ -38,23 +133,44 @@ impl Timer {
             action_info: "   ".to_string(),
             timer_type,
             repeat_times: 0,
+            paused: false,
+            marked: false,
+            started_at: None,
+            pause_count: 0,
+            paused_secs: 0,
+            ticked_secs: 0,
+            repeats_done: 0,
+            taskwarrior_uuid: None,
+            taskwarrior_started: false,
+            taskwarrior_failed: false,
+            pomodoro_cycle: None,
+            require_ack: false,
+            awaiting_ack: false,
+            finished_at: None,
+            snoozes: 0,
+            in_overtime: false,
+            subtasks: Vec::new(),
+            tags: Vec::new(),
+            expanded: false,
+            notification: None,
         }
     }
 
     pub fn formatted(&self) -> String {
-        let hours = self.timeleft_secs / 3600;
-        let minutes = (self.timeleft_secs % 3600) / 60;
-        let seconds = self.timeleft_secs % 60;
-
         format!(
-            "{:02}:{:02}:{:02} ({}){}     @{}:{}     {}",
-            hours,
-            minutes,
-            seconds,
+            "{}{}{}{} ({}){}     @{}:{}     {}",
+            format_clock(self.timeleft_secs),
+            if self.paused { " [paused]" } else { "" },
+            if self.require_ack { " [ack]" } else { "" },
+            self.checklist_progress()
+                .map_or(String::new(), |(done, total)| format!(
+                    " [{}/{}]",
+                    done, total
+                )),
             self.endtime.format("%Y-%m-%d %H:%M:%S"),
             self.action_info,
             self.id,
-            self.description,
+            self.description_with_tags(),
             if self.repeat_times > 0 {
                 format!("repeat: {}", self.repeat_times)
             } else {
@@ -63,21 +179,62 @@ impl Timer {
         )
     }
 
+    /// Ticks the timer unless it is paused; a paused timer still holds its column
+    pub fn paused_or_tick(&mut self) -> bool {
+        if self.paused {
+            self.is_active = true;
+            self.pause_tick();
+            return false;
+        }
+        self.tick()
+    }
+
+    /// Counts a second in which the timer was started but paused
+    pub fn pause_tick(&mut self) {
+        if self.started_at.is_some() {
+            self.paused_secs += 1;
+        }
+    }
+
+    /// Pauses or resumes the timer, counting the pauses after it was started
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused && self.started_at.is_some() {
+            self.pause_count += 1;
+        }
+    }
+
     pub fn tick(&mut self) -> bool {
         self.is_active = true;
+        if self.started_at.is_none() {
+            self.started_at = Some(Local::now());
+        }
         if self.timeleft_secs > 0 {
             self.timeleft_secs -= 1;
+            self.ticked_secs += 1;
             if self.timeleft_secs != 0 {
                 return false;
             }
 
-            Command::new("bash")
+            let _ = Command::new("bash")
                 .args(["-c", "echo -e \"\\a\" "])
-                .spawn()
-                .expect("Playing sound failed");
+                .spawn();
             self.is_active = false;
 
-            if cfg!(target_os = "linux") {
+            if cfg!(target_os = "linux") && self.repeat_times == 0 && notify_send_supports_actions()
+            {
+                self.close_notification();
+                self.notification = Command::new("notify-send")
+                    .args([
+                        "--action=snooze=Snooze",
+                        "--wait",
+                        "Timer beendet",
+                        &self.description,
+                    ])
+                    .stdout(Stdio::piped())
+                    .spawn()
+                    .ok();
+            } else if cfg!(target_os = "linux") {
                 let _ = Command::new("notify-send")
                     .args(["Timer beendet", &self.description])
                     .spawn();
@@ -89,11 +246,166 @@ impl Timer {
             if self.repeat_times > 0 {
                 self.timeleft_secs = self.initial_time;
                 self.repeat_times -= 1;
+                self.repeats_done += 1;
                 return false;
             }
 
+            self.finished_at = Some(Local::now());
+            self.in_overtime = true;
             return true;
         }
         false
     }
+
+    /// The description followed by the tags, e.g. "Report #clientA #review"
+    pub fn description_with_tags(&self) -> String {
+        let mut text = self.description.clone();
+        for tag in &self.tags {
+            text.push_str(" #");
+            text.push_str(tag);
+        }
+        text
+    }
+
+    /// Adds the tag or removes it if the timer has it already
+    pub fn toggle_tag(&mut self, tag: &str) {
+        if let Some(i) = self.tags.iter().position(|t| t == tag) {
+            self.tags.remove(i);
+        } else {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// Checked and total items of the checklist, if the timer has one
+    pub fn checklist_progress(&self) -> Option<(usize, usize)> {
+        if self.subtasks.is_empty() {
+            return None;
+        }
+        let done = self.subtasks.iter().filter(|s| s.done).count();
+        Some((done, self.subtasks.len()))
+    }
+
+    /// Whether the snooze button of the timer's notification was clicked since the last call.
+    /// notify-send prints the name of the clicked action when the notification is closed.
+    pub fn snooze_requested(&mut self) -> bool {
+        let Some(child) = &mut self.notification else {
+            return false;
+        };
+        match child.try_wait() {
+            Ok(None) => false,
+            Ok(Some(_)) => {
+                let mut output = String::new();
+                if let Some(stdout) = &mut child.stdout {
+                    let _ = stdout.read_to_string(&mut output);
+                }
+                self.notification = None;
+                output.trim() == "snooze"
+            }
+            Err(_) => {
+                self.notification = None;
+                false
+            }
+        }
+    }
+
+    /// Ends the notify-send process of the notification if it is still waiting for a click
+    pub fn close_notification(&mut self) {
+        if let Some(mut child) = self.notification.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+
+    /// Runs the finished timer again for the given time, tracked as a new run in the history
+    pub fn snooze(&mut self, secs: u64) {
+        self.initial_time = secs;
+        self.timeleft_secs = secs;
+        self.started_at = None;
+        self.finished_at = None;
+        self.pause_count = 0;
+        self.paused_secs = 0;
+        self.repeats_done = 0;
+        self.awaiting_ack = false;
+        self.in_overtime = false;
+        self.snoozes += 1;
+    }
+
+    /// Seconds since the timer finished
+    pub fn overtime_secs(&self) -> u64 {
+        self.finished_at
+            .map_or(0, |end| (Local::now() - end).num_seconds().max(0) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(script: &str) -> Option<Child> {
+        Command::new("sh")
+            .args(["-c", script])
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()
+    }
+
+    fn wait_for_exit(timer: &mut Timer) {
+        if let Some(child) = &mut timer.notification {
+            let _ = child.wait();
+        }
+    }
+
+    #[test]
+    fn splits_the_tags_off_a_description() {
+        let (description, tags) = split_tags("#clientA Write # report #urgent #clientA");
+        assert_eq!(description, "Write # report");
+        assert_eq!(tags, ["clientA", "urgent"]);
+        assert_eq!(split_tags("Report"), ("Report".to_string(), Vec::new()));
+    }
+
+    #[test]
+    fn notices_a_click_on_the_snooze_button_once() {
+        let mut timer = Timer::new("Write".to_string(), 60, true, None);
+        assert!(!timer.snooze_requested());
+
+        timer.notification = notification("echo snooze");
+        wait_for_exit(&mut timer);
+        assert!(timer.snooze_requested());
+        assert!(timer.notification.is_none());
+        assert!(!timer.snooze_requested());
+    }
+
+    #[test]
+    fn ignores_notifications_closed_without_snoozing() {
+        let mut timer = Timer::new("Write".to_string(), 60, true, None);
+        timer.notification = notification("echo default");
+        wait_for_exit(&mut timer);
+        assert!(!timer.snooze_requested());
+        assert!(timer.notification.is_none());
+
+        // a notification that is still shown is left alone until it is closed
+        timer.notification = notification("sleep 30");
+        assert!(!timer.snooze_requested());
+        assert!(timer.notification.is_some());
+        timer.close_notification();
+        assert!(timer.notification.is_none());
+    }
+
+    #[test]
+    fn snoozes_as_a_new_run() {
+        let mut timer = Timer::new("Write".to_string(), 60, true, None);
+        timer.timeleft_secs = 0;
+        timer.started_at = Some(Local::now());
+        timer.finished_at = Some(Local::now());
+        timer.pause_count = 2;
+        timer.paused_secs = 30;
+        timer.awaiting_ack = true;
+
+        timer.snooze(300);
+        assert_eq!((timer.initial_time, timer.timeleft_secs), (300, 300));
+        assert!(timer.started_at.is_none() && timer.finished_at.is_none());
+        assert_eq!((timer.pause_count, timer.paused_secs), (0, 0));
+        assert!(!timer.awaiting_ack);
+        assert_eq!(timer.snoozes, 1);
+    }
 }
@@ -1,5 +1,148 @@
+use chrono::{Local, NaiveDate};
+
 use crate::configuration::Configuration;
-use crate::timer::Timer;
+use crate::export::run_export;
+use crate::history::{record_timer, Outcome};
+use crate::import::import_file;
+use crate::taskwarrior::{finish_task, import_tasks, run_task_queue, sync_task_state};
+use crate::theme::theme_names;
+use crate::timer::{split_tags, Subtask, Timer};
+use crate::timer_types::run_type_command;
+use crate::ui_states::{StatsRange, STATISTICS_TAB};
+
+/// Advances the running timer of each column by one second, records finished timers in the
+/// history and moves them to the end if configured. Returns whether a timer finished in this
+/// tick, timers waiting for an acknowledgement don't count.
+pub fn tick_timers(config: &mut Configuration, pause_flag: bool) -> bool {
+    run_task_queue(config);
+    let snoozed: Vec<String> = config
+        .timers
+        .iter_mut()
+        .filter_map(|t| t.snooze_requested().then(|| t.id.to_string()))
+        .collect();
+    for id in snoozed {
+        snooze_timer(&id, "", config);
+    }
+    let running = config.running_timer_indices();
+    if pause_flag {
+        for &i in &running {
+            if config.timers[i].awaiting_ack {
+                continue;
+            }
+            config.timers[i].pause_tick();
+            sync_task_state(config, i, false);
+        }
+        config.update_timers();
+        return false;
+    }
+
+    let mut finished_this_tick = false;
+    let mut done_timers = Vec::new();
+    for &i in &running {
+        if config.timers[i].awaiting_ack {
+            continue;
+        }
+        let starting = config.timers[i].started_at.is_none();
+        let finished = config.timers[i].paused_or_tick();
+        if starting
+            && config.auto_focus_mode
+            && config.timers[i].started_at.is_some()
+            && config.timers[i].timer_type.as_deref() == Some("focus")
+        {
+            config.focus_mode = true;
+        }
+        if finished {
+            finished_this_tick = true;
+            // the timer that finished before in this column is no longer the one that just finished
+            for j in config.column_indices(config.timers[i].left_view) {
+                if j != i && !config.timers[j].awaiting_ack {
+                    end_overtime(config, j);
+                }
+            }
+            finish_task(config, i);
+            finish_pomodoro_phase(config, i, true);
+            if config.require_ack || config.timers[i].require_ack {
+                config.timers[i].awaiting_ack = true;
+            } else {
+                done_timers.push(i);
+            }
+        } else {
+            sync_task_state(config, i, !config.timers[i].paused);
+        }
+    }
+
+    if config.move_finished_timer {
+        for &i in done_timers.iter().rev() {
+            let t = config.timers.remove(i);
+            config.timers.push(t);
+        }
+        if !done_timers.is_empty() {
+            config.update_timers();
+        }
+    }
+    finished_this_tick
+}
+
+/// Records the timer in the history. History logging must not stop the timers, so a failed
+/// write is only shown in the status line.
+fn record_in_history(config: &mut Configuration, index: usize, outcome: Outcome) {
+    if let Err(e) = record_timer(&config.timers[index], outcome) {
+        config.status_message = Some(format!("Could not write history.jsonl: {}", e));
+    }
+}
+
+/// Records a finished timer as completed together with the time since it finished
+pub fn end_overtime(config: &mut Configuration, index: usize) {
+    if config.timers[index].in_overtime {
+        record_in_history(config, index, Outcome::Completed);
+        config.timers[index].in_overtime = false;
+    }
+}
+
+/// Ends the overtime of the finished timers that don't wait for an acknowledgement, called
+/// when the user presses a key or uses the mouse. The config is saved right away so that the
+/// timers aren't recorded twice.
+pub fn end_noticed_overtime(config: &mut Configuration) {
+    let noticed: Vec<usize> = (0..config.timers.len())
+        .filter(|&i| config.timers[i].in_overtime && !config.timers[i].awaiting_ack)
+        .collect();
+    for &i in &noticed {
+        end_overtime(config, i);
+    }
+    if !noticed.is_empty() {
+        if let Err(e) = config.write_config_to_file() {
+            config.status_message = Some(format!("Could not write config.json: {}", e));
+        }
+    }
+}
+
+/// Ends the overtime of all finished timers when the app quits, the time until the next start
+/// isn't overtime
+pub fn end_all_overtime(config: &mut Configuration) {
+    for i in 0..config.timers.len() {
+        end_overtime(config, i);
+    }
+}
+
+/// Records the timers that were still in overtime when the app was last closed without
+/// quitting. The time the app was closed isn't overtime, so they are recorded without it.
+pub fn end_stale_overtime(config: &mut Configuration) {
+    for i in 0..config.timers.len() {
+        if config.timers[i].in_overtime {
+            config.timers[i].in_overtime = false;
+            record_in_history(config, i, Outcome::Completed);
+        }
+    }
+}
+
+/// Counts a pause for the running timers when all timers are paused with Space
+pub fn count_global_pause(config: &mut Configuration) {
+    for i in config.running_timer_indices() {
+        if config.timers[i].started_at.is_some() && !config.timers[i].awaiting_ack {
+            config.timers[i].pause_count += 1;
+        }
+    }
+}
 
 pub fn add_timer(
     argument1: &String,
@@ -9,35 +152,312 @@ pub fn add_timer(
     reverse_adding: bool,
     color_input: Option<String>,
 ) {
-    let timer = config.create_timer_for_input(argument1, argument2, routine != "add2", color_input);
+    let (description, tags) = split_tags(argument2);
+    *argument2 = description;
+    let mut timer =
+        config.create_timer_for_input(argument1, argument2, routine != "add2", color_input);
+    timer.tags = tags;
     config.add_timer_to_config(timer, reverse_adding);
 }
 
-pub fn add_pomodoro_timer(config: &mut Configuration) {
-    let timer1 = Timer::new(
-        "Pomodoro-Timer".to_string(),
-        config.pomodoro_time * 60,
-        true,
-        Some(config.timer_colors["focus"].to_owned()),
-    );
-    let timer2 = Timer::new(
-        "Pomodoro-Break".to_string(),
-        if !config.timers.is_empty() && config.timers.len() % 6 == 0 {
-            config.pomodoro_bigbreak * 60
-        } else {
-            config.pomodoro_smallbreak * 60
-        },
-        true,
-        Some(config.timer_colors["break"].to_owned()),
-    );
+/// Cycle of the next planned pomodoro: it follows the last unfinished Pomodoro timer or, if
+/// none is planned, the pomodoros completed since the last long break
+fn next_pomodoro_cycle(config: &Configuration) -> u64 {
+    let cycles = config.pomodoro_cycles.max(1);
+    let last_planned = config
+        .timers
+        .iter()
+        .rev()
+        .filter(|t| t.left_view && t.timeleft_secs > 0)
+        .find_map(|t| t.pomodoro_cycle);
+    last_planned.unwrap_or(config.pomodoro_count) % cycles + 1
+}
 
-    config.add_timer_to_config(timer1, false);
-    config.add_timer_to_config(timer2, false);
+/// Plans the given number of work/break pairs at the end of the left column, so that the
+/// cycles run in order. The break after the last cycle of a set is the long break.
+pub fn add_pomodoro_timers(argument1: &str, config: &mut Configuration) {
+    let count = argument1.parse::<u64>().unwrap_or(1).max(1);
+    for _ in 0..count {
+        let cycle = next_pomodoro_cycle(config);
+        let long_break = cycle >= config.pomodoro_cycles.max(1);
+        let mut work = Timer::new(
+            "Pomodoro-Timer".to_string(),
+            config.pomodoro_time * 60,
+            true,
+            Some("focus".to_string()),
+        );
+        work.pomodoro_cycle = Some(cycle);
+        let mut pause = Timer::new(
+            "Pomodoro-Break".to_string(),
+            if long_break {
+                config.pomodoro_bigbreak * 60
+            } else {
+                config.pomodoro_smallbreak * 60
+            },
+            true,
+            Some("break".to_string()),
+        );
+        pause.pomodoro_cycle = Some(cycle);
+        config.timers.push(work);
+        config.timers.push(pause);
+    }
+}
+
+fn is_pomodoro_work(timer: &Timer) -> bool {
+    timer.pomodoro_cycle.is_some() && timer.timer_type.as_deref() != Some("break")
+}
+
+/// Advances the Pomodoro counter after a completed or skipped work or break timer. Without
+/// auto-continue the next pomodoro of the column is paused until the user resumes it.
+fn finish_pomodoro_phase(config: &mut Configuration, index: usize, completed: bool) {
+    let timer = &config.timers[index];
+    let Some(cycle) = timer.pomodoro_cycle else {
+        return;
+    };
+    if is_pomodoro_work(timer) {
+        if completed {
+            config.pomodoro_count += 1;
+        }
+        return;
+    }
+    if cycle >= config.pomodoro_cycles.max(1) {
+        config.pomodoro_count = 0;
+    }
+    if config.pomodoro_auto_continue {
+        return;
+    }
+    let next = config
+        .column_indices(timer.left_view)
+        .into_iter()
+        .find(|&i| i != index && config.timers[i].timeleft_secs > 0);
+    if let Some(i) = next.filter(|&i| is_pomodoro_work(&config.timers[i])) {
+        let next_timer = &mut config.timers[i];
+        next_timer.paused = true;
+        config.status_message = Some(format!(
+            "Break is over: run 'pause {}' or select it and press p to start pomodoro {}/{}",
+            next_timer.id,
+            next_timer.pomodoro_cycle.unwrap_or(1),
+            config.pomodoro_cycles
+        ));
+    }
+}
+
+/// Outcome of a timer that ends before it is finished
+fn unfinished_outcome(timer: &Timer, outcome: Outcome) -> Outcome {
+    if is_pomodoro_work(timer) && timer.started_at.is_some() {
+        Outcome::Interrupted
+    } else {
+        outcome
+    }
 }
 
 pub fn remove_timer(argument1: &str, config: &mut Configuration) {
     if let Ok(id) = argument1.parse::<u16>() {
-        config.timers.retain(|t| t.id != id);
+        remove_timers(&[id], config);
+    }
+}
+
+/// Removes the timers with the given ids and records the unfinished ones in the history
+pub fn remove_timers(ids: &[u16], config: &mut Configuration) {
+    for i in 0..config.timers.len() {
+        if !ids.contains(&config.timers[i].id) {
+            continue;
+        }
+        if config.timers[i].timeleft_secs > 0 {
+            let outcome = unfinished_outcome(&config.timers[i], Outcome::Removed);
+            record_in_history(config, i, outcome);
+            sync_task_state(config, i, false);
+        } else {
+            end_overtime(config, i);
+        }
+    }
+    for timer in config.timers.iter_mut().filter(|t| ids.contains(&t.id)) {
+        timer.close_notification();
+    }
+    config.timers.retain(|t| !ids.contains(&t.id));
+    config.clamp_selection();
+}
+
+pub fn clear_timers(config: &mut Configuration) {
+    let ids: Vec<u16> = config.timers.iter().map(|t| t.id).collect();
+    remove_timers(&ids, config);
+}
+
+/// Ends an unfinished timer now and records it as completed, e.g. when its checklist is done
+fn complete_timer(config: &mut Configuration, index: usize) {
+    let timer = &mut config.timers[index];
+    timer.timeleft_secs = 0;
+    timer.repeat_times = 0;
+    timer.paused = false;
+    timer.is_active = false;
+    timer.finished_at = Some(Local::now());
+    record_in_history(config, index, Outcome::Completed);
+    finish_task(config, index);
+    finish_pomodoro_phase(config, index, true);
+    if config.move_finished_timer {
+        let t = config.timers.remove(index);
+        config.timers.push(t);
+    }
+}
+
+/// Edits the checklist of a timer: `sub add [id] [text]`, `sub check [id] [number]` (checks or
+/// unchecks the item) and `sub rm [id] [number]`, items are numbered from 1
+pub fn edit_subtasks(args: &[&str], config: &mut Configuration) {
+    let (Some(&action), Some(Ok(id))) = (args.first(), args.get(1).map(|id| id.parse::<u16>()))
+    else {
+        return;
+    };
+    let Some(index) = config.timers.iter().position(|t| t.id == id) else {
+        return;
+    };
+    let item = args
+        .get(2)
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|&n| n >= 1 && n <= config.timers[index].subtasks.len())
+        .map(|n| n - 1);
+    let timer = &mut config.timers[index];
+    match action {
+        "add" if args.len() > 2 => timer.subtasks.push(Subtask {
+            description: args[2..].join(" "),
+            done: false,
+        }),
+        "check" => {
+            if let Some(item) = item {
+                timer.subtasks[item].done = !timer.subtasks[item].done;
+            }
+        }
+        "rm" => {
+            if let Some(item) = item {
+                timer.subtasks.remove(item);
+            }
+        }
+        _ => return,
+    }
+    let all_done = timer
+        .checklist_progress()
+        .is_some_and(|(done, total)| done == total);
+    if config.complete_on_checklist && all_done && config.timers[index].timeleft_secs > 0 {
+        complete_timer(config, index);
+        config.status_message = Some(format!("Checklist of timer {} is done", id));
+    }
+}
+
+/// Ends an unfinished timer now and records it as skipped
+pub fn skip_timer(argument1: &str, config: &mut Configuration) {
+    let Ok(id) = argument1.parse::<u16>() else {
+        return;
+    };
+    let Some(i) = config
+        .timers
+        .iter()
+        .position(|t| t.id == id && t.timeleft_secs > 0)
+    else {
+        return;
+    };
+    let timer = &mut config.timers[i];
+    timer.timeleft_secs = 0;
+    timer.repeat_times = 0;
+    timer.paused = false;
+    timer.is_active = false;
+    let outcome = unfinished_outcome(timer, Outcome::Skipped);
+    record_in_history(config, i, outcome);
+    sync_task_state(config, i, false);
+    finish_pomodoro_phase(config, i, false);
+    if config.move_finished_timer {
+        let t = config.timers.remove(i);
+        config.timers.push(t);
+    }
+}
+
+/// Lets the columns that wait for an acknowledgement continue with their next timer, either
+/// all of them or only the column of the timer with the given id
+pub fn acknowledge_timers(argument1: &str, config: &mut Configuration) {
+    let id = argument1.parse::<u16>().ok();
+    let waiting: Vec<usize> = (0..config.timers.len())
+        .filter(|&i| config.timers[i].awaiting_ack && id.is_none_or(|id| config.timers[i].id == id))
+        .collect();
+    for &i in &waiting {
+        end_overtime(config, i);
+        config.timers[i].awaiting_ack = false;
+        config.timers[i].close_notification();
+    }
+    if config.move_finished_timer {
+        for &i in waiting.iter().rev() {
+            let t = config.timers.remove(i);
+            config.timers.push(t);
+        }
+    }
+}
+
+/// Runs a finished timer again for the given minutes (default `snooze_minutes`) as the next
+/// timer of its column. Without an id the timer that finished last is snoozed.
+pub fn snooze_timer(argument1: &str, argument2: &str, config: &mut Configuration) {
+    let index = match argument1.parse::<u16>() {
+        Ok(id) => config
+            .timers
+            .iter()
+            .position(|t| t.id == id && t.timeleft_secs == 0),
+        Err(_) => (0..config.timers.len())
+            .filter(|&i| config.timers[i].finished_at.is_some())
+            .filter(|&i| config.timers[i].timeleft_secs == 0)
+            .max_by_key(|&i| config.timers[i].finished_at),
+    };
+    let Some(index) = index else {
+        return;
+    };
+    let minutes = argument2.parse::<u64>().unwrap_or(config.snooze_minutes);
+    end_overtime(config, index);
+    let mut timer = config.timers.remove(index);
+    timer.snooze(minutes * 60);
+    // back in front of the timers that haven't run yet, where it was when it finished
+    let position = config
+        .timers
+        .iter()
+        .position(|t| t.left_view == timer.left_view && (t.timeleft_secs > 0 || t.awaiting_ack))
+        .unwrap_or(config.timers.len());
+    config.status_message = Some(format!(
+        "Snoozed '{}' for {} min",
+        timer.description, minutes
+    ));
+    config.timers.insert(position, timer);
+    config.clamp_selection();
+}
+
+/// Adds the given `#tags` to the timer with the given id, or removes those it has already
+pub fn toggle_tags(argument1: &str, argument2: &str, config: &mut Configuration) {
+    let Ok(id) = argument1.parse::<u16>() else {
+        return;
+    };
+    if let Some(timer) = config.timers.iter_mut().find(|t| t.id == id) {
+        for tag in split_tags(argument2).1 {
+            timer.toggle_tag(&tag);
+        }
+    }
+}
+
+/// Only shows the timers with the given `#tag`, or all timers again without one
+pub fn set_tag_filter(argument1: &str, config: &mut Configuration) {
+    config.tag_filter = argument1
+        .strip_prefix('#')
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string);
+    config.clamp_selection();
+}
+
+/// Toggles whether the column waits for an acknowledgement after the timer with the given id
+pub fn toggle_ack_timer(argument1: &str, config: &mut Configuration) {
+    if let Ok(id) = argument1.parse::<u16>() {
+        if let Some(timer) = config.timers.iter_mut().find(|t| t.id == id) {
+            timer.require_ack = !timer.require_ack;
+        }
+    }
+}
+
+pub fn toggle_pause_timer(argument1: &str, config: &mut Configuration) {
+    if let Ok(id) = argument1.parse::<u16>() {
+        if let Some(timer) = config.timers.iter_mut().find(|t| t.id == id) {
+            timer.toggle_pause();
+        }
     }
 }
 
@@ -173,6 +593,36 @@ pub fn repeat_timer(argument1: &str, argument2: &str, config: &mut Configuration
     }
 }
 
+/// Shows the statistics for a custom date range, given as YYYY-MM-DD
+pub fn set_stats_range(argument1: &str, argument2: &str, config: &mut Configuration) {
+    let from = NaiveDate::parse_from_str(argument1, "%Y-%m-%d");
+    let to = if argument2.is_empty() {
+        from
+    } else {
+        NaiveDate::parse_from_str(argument2, "%Y-%m-%d")
+    };
+    if let (Ok(from), Ok(to)) = (from, to) {
+        config.stats_custom_range = Some((from.min(to), from.max(to)));
+        config.stats_range = StatsRange::Custom;
+        config.index = STATISTICS_TAB;
+    }
+}
+
+/// Switches to the theme, without a name lists the available themes
+pub fn set_theme(argument1: &str, config: &mut Configuration) {
+    let names = theme_names();
+    if !names.iter().any(|name| name == argument1) {
+        config.status_message = Some(format!("Themes: {}", names.join(", ")));
+        return;
+    }
+    config.theme = argument1.to_string();
+    config.sync_setting("theme");
+    config.status_message = Some(match config.apply_theme() {
+        Ok(()) => format!("Theme '{}'", argument1),
+        Err(e) => e,
+    });
+}
+
 pub fn parse_input(input: &str, config: &mut Configuration) {
     if input.is_empty() {
         return;
@@ -183,16 +633,14 @@ pub fn parse_input(input: &str, config: &mut Configuration) {
     let argument1 = parts.next().unwrap_or("").to_string();
     let mut collected_argument2 = parts.collect::<Vec<&str>>();
 
-    // check if the 3rd argument is a valid color
-    let color_input = if collected_argument2.len() > 0
+    // check if the 3rd argument is a valid timer type
+    let color_input = if !collected_argument2.is_empty()
         && config
             .timer_colors
             .contains_key(&collected_argument2[0].to_lowercase())
     {
-        let color = Some(config.timer_colors[&collected_argument2[0].to_lowercase()].to_owned());
-        // remove the color argument from the input
-        collected_argument2.remove(0);
-        color
+        // remove the type argument from the input
+        Some(collected_argument2.remove(0).to_lowercase())
     } else {
         None
     };
@@ -221,13 +669,35 @@ pub fn parse_input(input: &str, config: &mut Configuration) {
             );
         }
         "addp" => {
-            add_pomodoro_timer(config);
+            add_pomodoro_timers(&argument1, config);
         }
         "rm" => {
             remove_timer(&argument1, config);
         }
         "clear" => {
-            config.timers.clear();
+            clear_timers(config);
+        }
+        "next" => {
+            acknowledge_timers(&argument1, config);
+        }
+        "sub" => {
+            let args: Vec<&str> = input.split_whitespace().skip(1).collect();
+            edit_subtasks(&args, config);
+        }
+        "tag" => {
+            toggle_tags(&argument1, &argument2, config);
+        }
+        "filter" => {
+            set_tag_filter(&argument1, config);
+        }
+        "snooze" => {
+            snooze_timer(&argument1, &argument2, config);
+        }
+        "ack" => {
+            toggle_ack_timer(&argument1, config);
+        }
+        "skip" => {
+            skip_timer(&argument1, config);
         }
         "mv" | "move" => {
             move_timer(&argument1, &argument2, config);
@@ -253,8 +723,369 @@ pub fn parse_input(input: &str, config: &mut Configuration) {
         "repeat" => {
             repeat_timer(&argument1, &argument2, config);
         }
+        "pause" => {
+            toggle_pause_timer(&argument1, config);
+        }
+        "stats" => {
+            set_stats_range(&argument1, &argument2, config);
+        }
+        "tw" | "taskwarrior" if argument1 == "import" => {
+            let filter: Vec<&str> = input.split_whitespace().skip(2).collect();
+            config.status_message = Some(match import_tasks(config, &filter) {
+                Ok(imported) => format!("Imported {} tasks from Taskwarrior", imported),
+                Err(e) => format!("Taskwarrior import failed: {}", e),
+            });
+        }
+        "import" => {
+            let path = input
+                .split_whitespace()
+                .skip(1)
+                .collect::<Vec<&str>>()
+                .join(" ");
+            config.status_message = Some(match import_file(&path, config) {
+                Ok(imported) => format!("Imported {} items from {}", imported, path),
+                Err(e) => format!("Import failed: {}", e),
+            });
+        }
+        "theme" => {
+            set_theme(&argument1, config);
+        }
+        "type" => {
+            let args: Vec<&str> = input.split_whitespace().skip(1).collect();
+            config.status_message = Some(match run_type_command(&args, config) {
+                Ok(message) => message,
+                Err(e) => e,
+            });
+        }
+        "export" => {
+            let args: Vec<&str> = input.split_whitespace().skip(1).collect();
+            config.status_message = Some(match run_export(&args, config) {
+                Ok(path) => format!("Exported to {}", path),
+                Err(e) => format!("Export failed: {}", e),
+            });
+        }
         _ => {}
     }
     config.write_config_to_file().unwrap();
     config.update_timers();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn planned(config: &Configuration) -> Vec<(Option<u64>, u64)> {
+        config
+            .timers
+            .iter()
+            .map(|t| (t.pomodoro_cycle, t.initial_time / 60))
+            .collect()
+    }
+
+    #[test]
+    fn plans_cycles_after_the_completed_pomodoros() {
+        let mut config = Configuration::new();
+        config.pomodoro_count = 2;
+        assert_eq!(next_pomodoro_cycle(&config), 3);
+
+        add_pomodoro_timers("3", &mut config);
+        // the break after the fourth pomodoro is the long one
+        assert_eq!(
+            planned(&config),
+            [
+                (Some(3), 25),
+                (Some(3), 5),
+                (Some(4), 25),
+                (Some(4), 10),
+                (Some(1), 25),
+                (Some(1), 5)
+            ]
+        );
+        // planned timers count before the completed ones
+        config.pomodoro_count = 0;
+        assert_eq!(next_pomodoro_cycle(&config), 2);
+    }
+
+    #[test]
+    fn counts_completed_pomodoros_until_the_long_break() {
+        let mut config = Configuration::new();
+        config.pomodoro_cycles = 2;
+        add_pomodoro_timers("2", &mut config);
+        config.update_timers();
+
+        finish_pomodoro_phase(&mut config, 0, true);
+        finish_pomodoro_phase(&mut config, 1, true);
+        assert_eq!(config.pomodoro_count, 1);
+        // a skipped pomodoro doesn't count
+        finish_pomodoro_phase(&mut config, 2, false);
+        assert_eq!(config.pomodoro_count, 1);
+        finish_pomodoro_phase(&mut config, 3, true);
+        assert_eq!(config.pomodoro_count, 0);
+    }
+
+    #[test]
+    fn pauses_the_next_pomodoro_without_auto_continue() {
+        let mut config = Configuration::new();
+        config.pomodoro_auto_continue = false;
+        add_pomodoro_timers("2", &mut config);
+        config.update_timers();
+        config.timers[0].timeleft_secs = 0;
+        config.timers[1].timeleft_secs = 0;
+
+        finish_pomodoro_phase(&mut config, 1, true);
+        assert!(config.timers[2].paused);
+        assert!(!config.timers[3].paused);
+        assert_eq!(
+            config.status_message.as_deref(),
+            Some("Break is over: run 'pause 2' or select it and press p to start pomodoro 2/4")
+        );
+    }
+
+    #[test]
+    fn interrupts_only_started_pomodoros() {
+        let mut config = Configuration::new();
+        add_pomodoro_timers("1", &mut config);
+        assert!(unfinished_outcome(&config.timers[0], Outcome::Removed) == Outcome::Removed);
+
+        for timer in config.timers.iter_mut() {
+            timer.started_at = Some(Local::now());
+        }
+        assert!(unfinished_outcome(&config.timers[0], Outcome::Skipped) == Outcome::Interrupted);
+        assert!(unfinished_outcome(&config.timers[1], Outcome::Skipped) == Outcome::Skipped);
+        let mut plain = Timer::new("Report".to_string(), 60, true, Some("focus".to_string()));
+        plain.started_at = Some(Local::now());
+        assert!(unfinished_outcome(&plain, Outcome::Removed) == Outcome::Removed);
+    }
+
+    fn finished_timer(config: &mut Configuration, description: &str) {
+        let mut timer = Timer::new(description.to_string(), 60, true, None);
+        timer.timeleft_secs = 0;
+        timer.started_at = Some(Local::now());
+        timer.finished_at = Some(Local::now());
+        config.timers.push(timer);
+    }
+
+    #[test]
+    fn holds_the_column_until_the_timer_is_acknowledged() {
+        let mut config = Configuration::new();
+        finished_timer(&mut config, "Write");
+        config.timers[0].awaiting_ack = true;
+        config
+            .timers
+            .push(Timer::new("Review".to_string(), 60, true, None));
+        config.update_timers();
+
+        assert!(!tick_timers(&mut config, false));
+        assert_eq!(config.timers[1].timeleft_secs, 60);
+        assert!(config.timers[0].awaiting_ack);
+        // the action after timers must not run while the column waits
+        assert!(!config.check_all_timers_done());
+
+        config.move_finished_timer = false;
+        acknowledge_timers("", &mut config);
+        assert!(!config.timers[0].awaiting_ack);
+        assert!(!tick_timers(&mut config, false));
+        assert_eq!(config.timers[1].timeleft_secs, 59);
+    }
+
+    #[test]
+    fn acknowledges_only_the_column_of_the_given_timer() {
+        let mut config = Configuration::new();
+        finished_timer(&mut config, "Write");
+        finished_timer(&mut config, "Read");
+        config.timers[1].left_view = false;
+        for timer in config.timers.iter_mut() {
+            timer.awaiting_ack = true;
+        }
+        config.update_timers();
+
+        acknowledge_timers("1", &mut config);
+        assert!(config.timers[0].awaiting_ack);
+        assert!(!config.timers[1].awaiting_ack);
+        // an unknown id acknowledges nothing
+        acknowledge_timers("7", &mut config);
+        assert!(config.timers[0].awaiting_ack);
+    }
+
+    #[test]
+    fn toggles_the_acknowledgement_of_a_timer() {
+        let mut config = Configuration::new();
+        config
+            .timers
+            .push(Timer::new("Write".to_string(), 300, true, None));
+        config.update_timers();
+        toggle_ack_timer("0", &mut config);
+        assert!(config.timers[0].require_ack);
+        toggle_ack_timer("0", &mut config);
+        assert!(!config.timers[0].require_ack);
+    }
+
+    fn descriptions<'a>(config: &'a Configuration) -> Vec<&'a str> {
+        config
+            .timers
+            .iter()
+            .map(|t| t.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn snoozes_the_timer_that_finished_last_before_the_waiting_timers() {
+        let mut config = Configuration::new();
+        finished_timer(&mut config, "Write");
+        finished_timer(&mut config, "Read");
+        config.timers[0].finished_at = Some(Local::now() - chrono::Duration::minutes(5));
+        config
+            .timers
+            .push(Timer::new("Review".to_string(), 60, true, None));
+        config.update_timers();
+
+        snooze_timer("", "", &mut config);
+        assert_eq!(descriptions(&config), ["Write", "Read", "Review"]);
+        assert_eq!(config.timers[1].timeleft_secs, config.snooze_minutes * 60);
+        assert_eq!(
+            config.status_message.as_deref(),
+            Some("Snoozed 'Read' for 5 min")
+        );
+
+        snooze_timer("0", "10", &mut config);
+        assert_eq!(descriptions(&config), ["Write", "Read", "Review"]);
+        assert_eq!(config.timers[0].timeleft_secs, 600);
+    }
+
+    #[test]
+    fn snoozes_only_finished_timers() {
+        let mut config = Configuration::new();
+        config
+            .timers
+            .push(Timer::new("Review".to_string(), 60, true, None));
+        config.update_timers();
+        snooze_timer("0", "", &mut config);
+        snooze_timer("", "", &mut config);
+        assert_eq!(config.timers[0].timeleft_secs, 60);
+        assert!(config.status_message.is_none());
+    }
+
+    fn checklist<'a>(config: &'a Configuration) -> Vec<(&'a str, bool)> {
+        config.timers[0]
+            .subtasks
+            .iter()
+            .map(|s| (s.description.as_str(), s.done))
+            .collect()
+    }
+
+    fn sub(command: &str, config: &mut Configuration) {
+        let args: Vec<&str> = command.split_whitespace().collect();
+        edit_subtasks(&args, config);
+    }
+
+    #[test]
+    fn edits_the_checklist_of_a_timer() {
+        let mut config = Configuration::new();
+        config
+            .timers
+            .push(Timer::new("Report".to_string(), 60, true, None));
+        config.update_timers();
+        assert_eq!(config.timers[0].checklist_progress(), None);
+
+        sub("add 0 Write the intro", &mut config);
+        sub("add 0 Proofread", &mut config);
+        sub("check 0 2", &mut config);
+        assert_eq!(
+            checklist(&config),
+            [("Write the intro", false), ("Proofread", true)]
+        );
+        assert_eq!(config.timers[0].checklist_progress(), Some((1, 2)));
+
+        // items are numbered from 1, unknown items and timers are ignored
+        sub("check 0 0", &mut config);
+        sub("rm 0 3", &mut config);
+        sub("add 4 Print", &mut config);
+        sub("check 0 2", &mut config);
+        sub("rm 0 1", &mut config);
+        assert_eq!(checklist(&config), [("Proofread", false)]);
+    }
+
+    #[test]
+    fn completes_a_timer_only_if_configured() {
+        let mut config = Configuration::new();
+        config
+            .timers
+            .push(Timer::new("Report".to_string(), 60, true, None));
+        config.update_timers();
+        sub("add 0 Proofread", &mut config);
+        sub("check 0 1", &mut config);
+        assert_eq!(config.timers[0].timeleft_secs, 60);
+        assert!(config.status_message.is_none());
+    }
+
+    #[test]
+    fn toggles_the_tags_of_a_timer() {
+        let mut config = Configuration::new();
+        let mut timer = Timer::new("Report".to_string(), 60, true, None);
+        timer.tags = vec!["clientA".to_string()];
+        config.timers.push(timer);
+        config.update_timers();
+
+        toggle_tags("0", "#urgent #clientA plain", &mut config);
+        assert_eq!(config.timers[0].tags, ["urgent"]);
+        toggle_tags("3", "#clientA", &mut config);
+        assert_eq!(config.timers[0].tags, ["urgent"]);
+    }
+
+    #[test]
+    fn shows_only_the_timers_with_the_filtered_tag() {
+        let mut config = Configuration::new();
+        for (description, tag) in [
+            ("Report", "clientA"),
+            ("Call", "clientB"),
+            ("Mail", "clientA"),
+        ] {
+            let mut timer = Timer::new(description.to_string(), 60, true, None);
+            timer.tags = vec![tag.to_string()];
+            config.timers.push(timer);
+        }
+        config.update_timers();
+
+        set_tag_filter("#clientA", &mut config);
+        assert_eq!(config.tag_filter.as_deref(), Some("clientA"));
+        assert_eq!(config.visible_column_indices(true), [0, 2]);
+        assert!(config.visible_column_indices(false).is_empty());
+
+        // without a # the filter is cleared
+        set_tag_filter("clientA", &mut config);
+        assert_eq!(config.tag_filter, None);
+        assert_eq!(config.visible_column_indices(true), [0, 1, 2]);
+    }
+
+    #[test]
+    fn enters_the_focus_mode_when_a_focus_timer_starts() {
+        let mut config = Configuration::new();
+        let focus = Some("focus".to_string());
+        config.timers.push(Timer::new(
+            "Break".to_string(),
+            60,
+            true,
+            Some("break".to_string()),
+        ));
+        config
+            .timers
+            .push(Timer::new("Write".to_string(), 60, false, focus.clone()));
+        config.update_timers();
+        tick_timers(&mut config, false);
+        assert!(!config.focus_mode);
+
+        config.auto_focus_mode = true;
+        config
+            .timers
+            .push(Timer::new("Read".to_string(), 60, true, focus));
+        config.update_timers();
+        tick_timers(&mut config, false);
+        // only the start of a focus timer enters it, not a timer that was already running
+        assert!(!config.focus_mode);
+        config.timers.remove(0);
+        config.update_timers();
+        tick_timers(&mut config, false);
+        assert!(config.focus_mode);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::color::AcceptedColors;
+use crate::configuration::Configuration;
+
+/// Types the Pomodoro timers and the statistics rely on, they can be recolored but not removed
+/// or renamed
+pub const BUILT_IN_TYPES: [&str; 2] = ["focus", "break"];
+
+/// The names of the timer types in alphabetical order
+pub fn type_names(config: &Configuration) -> Vec<String> {
+    let mut names: Vec<String> = config.timer_colors.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Whether the configured color of the type is one of the accepted colors
+pub fn has_valid_color(config: &Configuration, name: &str) -> bool {
+    config
+        .timer_colors
+        .get(name)
+        .is_some_and(|color| AcceptedColors::parse(color).is_some())
+}
+
+fn parse_color(color: &str) -> Result<String, String> {
+    AcceptedColors::parse(color)
+        .map(|color| color.to_string())
+        .ok_or_else(|| {
+            format!(
+                "Unknown color '{}', use one of: {}",
+                color,
+                AcceptedColors::names()
+            )
+        })
+}
+
+/// Type names are single lowercase words, because they are matched as the third word of `add`
+fn parse_name(name: &str) -> Result<String, String> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() || name.contains(char::is_whitespace) || name.starts_with('#') {
+        return Err(format!("Invalid type name '{}'", name));
+    }
+    if name.parse::<u64>().is_ok() {
+        return Err(format!("Type name '{}' can't be a number", name));
+    }
+    Ok(name)
+}
+
+fn existing_name(config: &Configuration, name: &str) -> Result<String, String> {
+    let name = name.to_lowercase();
+    if config.timer_colors.contains_key(&name) {
+        Ok(name)
+    } else {
+        Err(format!("Unknown timer type '{}'", name))
+    }
+}
+
+fn user_defined_name(config: &Configuration, name: &str) -> Result<String, String> {
+    let name = existing_name(config, name)?;
+    if BUILT_IN_TYPES.contains(&name.as_str()) {
+        return Err(format!(
+            "Timer type '{}' is built in and can't be removed or renamed",
+            name
+        ));
+    }
+    Ok(name)
+}
+
+pub fn add_type(config: &mut Configuration, name: &str, color: &str) -> Result<String, String> {
+    let name = parse_name(name)?;
+    if config.timer_colors.contains_key(&name) {
+        return Err(format!("Timer type '{}' exists already", name));
+    }
+    let color = parse_color(color)?;
+    let message = format!("Added timer type '{}' ({})", name, color);
+    config.timer_colors.insert(name, color);
+    Ok(message)
+}
+
+/// Removes the type, its timers keep running without a type
+pub fn remove_type(config: &mut Configuration, name: &str) -> Result<String, String> {
+    let name = user_defined_name(config, name)?;
+    config.timer_colors.remove(&name);
+    for timer in &mut config.timers {
+        if timer.timer_type.as_deref() == Some(name.as_str()) {
+            timer.timer_type = None;
+        }
+    }
+    Ok(format!("Removed timer type '{}'", name))
+}
+
+pub fn set_type_color(
+    config: &mut Configuration,
+    name: &str,
+    color: &str,
+) -> Result<String, String> {
+    let name = existing_name(config, name)?;
+    let color = parse_color(color)?;
+    let message = format!("Timer type '{}' is now {}", name, color);
+    config.timer_colors.insert(name, color);
+    Ok(message)
+}
+
+/// Renames the type, its timers are renamed with it
+pub fn rename_type(
+    config: &mut Configuration,
+    name: &str,
+    new_name: &str,
+) -> Result<String, String> {
+    let name = user_defined_name(config, name)?;
+    let new_name = parse_name(new_name)?;
+    if new_name == name {
+        return Ok(format!("Timer type '{}' is unchanged", name));
+    }
+    if config.timer_colors.contains_key(&new_name) {
+        return Err(format!("Timer type '{}' exists already", new_name));
+    }
+    let color = config.timer_colors.remove(&name).unwrap_or_default();
+    config.timer_colors.insert(new_name.clone(), color);
+    for timer in &mut config.timers {
+        if timer.timer_type.as_deref() == Some(name.as_str()) {
+            timer.timer_type = Some(new_name.clone());
+        }
+    }
+    Ok(format!("Renamed timer type '{}' to '{}'", name, new_name))
+}
+
+/// Moves the color of the type to the next or previous accepted color. An invalid color
+/// starts over at Green, the color it was shown in.
+pub fn cycle_type_color(config: &mut Configuration, name: &str, forward: bool) {
+    if let Some(color) = config.timer_colors.get_mut(name) {
+        let current = AcceptedColors::parse(color).unwrap_or(AcceptedColors::Green);
+        let next = if forward {
+            current.next_color()
+        } else {
+            current.previous_color()
+        };
+        *color = next.to_string();
+    }
+}
+
+/// Runs `type add|rm|color|rename ...` and returns the message for the status line
+pub fn run_type_command(args: &[&str], config: &mut Configuration) -> Result<String, String> {
+    match args {
+        ["add", name] => add_type(config, name, "Green"),
+        ["add", name, color] => add_type(config, name, color),
+        ["rm", name] => remove_type(config, name),
+        ["color", name, color] => set_type_color(config, name, color),
+        ["rename", name, new_name] => rename_type(config, name, new_name),
+        _ => Err(
+            "Usage: type add [name] [color], type rm [name], type color [name] [color], \
+             type rename [name] [new name]"
+                .to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timer::Timer;
+
+    fn config_with_reading_timer() -> Configuration<'static> {
+        let mut config = Configuration::new();
+        add_type(&mut config, "Reading", "blue").unwrap();
+        config.timers.push(Timer::new(
+            "Paper".to_string(),
+            60,
+            true,
+            Some("reading".to_string()),
+        ));
+        config
+    }
+
+    #[test]
+    fn adds_types_with_a_valid_name_and_color() {
+        let mut config = config_with_reading_timer();
+        assert_eq!(config.timer_colors["reading"], "Blue");
+        assert!(type_names(&config).contains(&"reading".to_string()));
+
+        assert!(add_type(&mut config, "reading", "Red").is_err());
+        assert!(add_type(&mut config, "deep work", "Red").is_err());
+        assert!(add_type(&mut config, "#deep", "Red").is_err());
+        assert!(add_type(&mut config, "42", "Red").is_err());
+        assert!(add_type(&mut config, "writing", "Purple").is_err());
+        assert!(!config.timer_colors.contains_key("writing"));
+    }
+
+    #[test]
+    fn renames_a_type_together_with_its_timers() {
+        let mut config = config_with_reading_timer();
+        assert_eq!(
+            run_type_command(&["rename", "reading", "Papers"], &mut config),
+            Ok("Renamed timer type 'reading' to 'papers'".to_string())
+        );
+        assert_eq!(config.timer_colors["papers"], "Blue");
+        assert!(!config.timer_colors.contains_key("reading"));
+        assert_eq!(config.timers[0].timer_type.as_deref(), Some("papers"));
+        assert!(rename_type(&mut config, "papers", "focus").is_err());
+        assert!(rename_type(&mut config, "reading", "books").is_err());
+    }
+
+    #[test]
+    fn removes_a_type_and_keeps_its_timers() {
+        let mut config = config_with_reading_timer();
+        assert!(remove_type(&mut config, "READING").is_ok());
+        assert!(!config.timer_colors.contains_key("reading"));
+        assert_eq!(config.timers.len(), 1);
+        assert_eq!(config.timers[0].timer_type, None);
+        assert!(remove_type(&mut config, "reading").is_err());
+    }
+
+    #[test]
+    fn keeps_the_built_in_types() {
+        let mut config = config_with_reading_timer();
+        for name in BUILT_IN_TYPES {
+            assert!(remove_type(&mut config, name).is_err());
+            assert!(rename_type(&mut config, name, "other").is_err());
+            assert!(config.timer_colors.contains_key(name));
+        }
+        assert!(set_type_color(&mut config, "break", "Cyan").is_ok());
+        assert_eq!(config.timer_colors["break"], "Cyan");
+    }
+}
@@ -1,23 +1,59 @@
 use std::{fs, str::FromStr};
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 
 use ratatui::{
     backend::Backend,
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Clear, Gauge, Paragraph, Row, Table, Tabs, Wrap},
+    widgets::{
+        BarChart, Block, BorderType, Borders, Cell, Clear, Gauge, Paragraph, Row, Sparkline, Table,
+        Tabs, Wrap,
+    },
     Frame,
 };
 use std::io;
 
-use crate::color::{get_background_color, get_foreground_color, AcceptedColors};
+use crate::big_digits::{self, countdown_text};
+use crate::color::{color_support, index_to_rgb, AcceptedColors};
 use crate::configuration::Configuration;
 use crate::input_field::InputField;
-use crate::timer::Timer;
-use crate::timer_logic::parse_input;
-use crate::ui_states::UiState;
+use crate::keybindings::{Action, Context};
+use crate::settings::{SettingKind, SETTINGS};
+use crate::statistics::{format_duration, Statistics};
+use crate::timer::{format_clock, Timer};
+use crate::timer_logic::{
+    acknowledge_timers, count_global_pause, decrease_timer, increase_timer, parse_input,
+    remove_timers, skip_timer, snooze_timer, toggle_pause_timer,
+};
+use crate::timer_types::{
+    add_type, cycle_type_color, has_valid_color, remove_type, rename_type, set_type_color,
+    type_names,
+};
+use crate::ui_states::{
+    ColorPicker, ColorTarget, InputMode, SortColumn, TypeEdit, UiState, STATISTICS_TAB,
+};
+use crate::utils::truncate;
+
+/// Where a key goes: the focus mode, an input being typed or the current tab
+fn key_context(config: &Configuration) -> Context {
+    if config.focus_mode {
+        return Context::Focus;
+    }
+    match UiState::get_current_ui(config.index) {
+        UiState::TimerUi if config.input_mode == InputMode::Normal => Context::Timer,
+        UiState::TimerUi => Context::Input,
+        UiState::SetsUi => Context::Sets,
+        UiState::ConfigUi if config.color_picker.is_some() => Context::ColorPicker,
+        UiState::ConfigUi if config.type_edit.is_some() || config.setting_edit.is_some() => {
+            Context::Input
+        }
+        UiState::ConfigUi if config.types_focus => Context::TimerTypes,
+        UiState::ConfigUi => Context::Config,
+        UiState::StatisticsUi => Context::Statistics,
+    }
+}
 
 pub fn handle_key_press(
     key: KeyEvent,
@@ -25,20 +61,57 @@ pub fn handle_key_press(
     input_field: &mut InputField,
     pause_flag: &mut bool,
 ) -> Result<(), io::Error> {
-    let current_ui = UiState::get_current_ui(config.index);
-
-    match current_ui {
-        UiState::TimerUi => match key.code {
-            KeyCode::Tab => config.next(),
-            KeyCode::Left => input_field.move_cursor_left(),
-            KeyCode::Right => input_field.move_cursor_right(),
-            KeyCode::Up => input_field.move_history_up(),
-            KeyCode::Down => input_field.move_history_down(),
-            KeyCode::Esc => {
+    let context = key_context(config);
+    let action = config.key_map.action(context, &key);
+    match action {
+        Some(Action::Quit) => {
+            config.quit = true;
+            return Ok(());
+        }
+        Some(Action::NextTab) => {
+            if !config.focus_mode {
+                config.next();
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    match context {
+        Context::Focus => handle_focus_key(action, config, pause_flag),
+        Context::Input if config.type_edit.is_some() => handle_type_name_key(key, action, config),
+        Context::Input if config.setting_edit.is_some() => {
+            handle_setting_edit_key(key, action, config)
+        }
+        Context::Input if config.input_mode == InputMode::Search => match action {
+            Some(Action::Cancel) => {
+                config.search_query.clear();
+                config.input_mode = InputMode::Normal;
+            }
+            Some(Action::Run) => config.input_mode = InputMode::Normal,
+            Some(Action::DeleteChar) => {
+                config.search_query.pop();
+                config.select_first_visible();
+            }
+            _ => {
+                if let KeyCode::Char(c) = key.code {
+                    config.search_query.push(c);
+                    config.select_first_visible();
+                }
+            }
+        },
+        Context::Input => match action {
+            Some(Action::CursorLeft) => input_field.move_cursor_left(),
+            Some(Action::CursorRight) => input_field.move_cursor_right(),
+            Some(Action::HistoryPrevious) => input_field.move_history_up(),
+            Some(Action::HistoryNext) => input_field.move_history_down(),
+            Some(Action::Cancel) => {
                 input_field.content.clear();
                 input_field.cursor_position = 0;
+                config.input_mode = InputMode::Normal;
             }
-            KeyCode::Enter => {
+            Some(Action::Run) => {
+                config.status_message = None;
                 parse_input(&input_field.content, config);
                 input_field
                     .content_history
@@ -46,132 +119,698 @@ pub fn handle_key_press(
                 input_field.history_position += 1;
                 input_field.content.clear();
                 input_field.cursor_position = 0;
+                config.input_mode = InputMode::Normal;
             }
-            KeyCode::Char(c) => match c {
-                'h' => {
-                    if input_field.content.is_empty() {
-                        config.show_popup = !config.show_popup;
-                    } else {
-                        input_field.insert_char(c)
-                    }
-                }
-                ' ' => {
-                    if input_field.content.is_empty() {
-                        *pause_flag = !*pause_flag;
-                    } else {
-                        input_field.insert_char(c)
-                    }
+            Some(Action::DeleteChar) => input_field.delete_char(),
+            _ => {
+                if let KeyCode::Char(c) = key.code {
+                    input_field.insert_char(c);
                 }
-                _ => input_field.insert_char(c),
-            },
-            KeyCode::Backspace => input_field.delete_char(),
-            _ => {}
+            }
         },
-        UiState::SetsUi => match key.code {
-            KeyCode::Tab => config.next(),
-            KeyCode::Esc => {
+        Context::Timer => handle_timer_navigation(action, config, input_field, pause_flag),
+        Context::Sets => match action {
+            Some(Action::SaveSet) => {
                 let files_len = config.read_set_files().unwrap().len();
-                config.write_set_to_file(format!("testset{}", files_len)).unwrap()
+                config
+                    .write_set_to_file(format!("testset{}", files_len))
+                    .unwrap()
             }
-            KeyCode::Delete | KeyCode::Backspace => {
-                let files = config.read_set_files().unwrap();
-                config.delete_set_file();
+            Some(Action::Delete) => {
+                let _ = config.delete_set_file();
             }
-            KeyCode::Enter => {
+            Some(Action::ApplySet) => {
                 let timers = config.apply_set().unwrap();
+                config.close_notifications();
                 config.timers = timers;
                 config.update_timers();
             }
-            KeyCode::Up => config.previous_table_entry(),
-            KeyCode::Down => config.next_table_entry(),
+            Some(Action::SelectPrevious) => config.previous_table_entry(),
+            Some(Action::SelectNext) => config.next_table_entry(),
+            _ => {}
+        },
+        Context::ColorPicker => handle_color_picker_key(key, action, config),
+        Context::TimerTypes => handle_timer_types_key(action, config),
+        Context::Config => match action {
+            Some(Action::PickColor) => open_color_picker(config),
+            Some(Action::EditTypes) => {
+                config.types_focus = true;
+                config.status_message = None;
+            }
+            Some(Action::ClearValue) => config.clear_table_entry(),
+            Some(Action::EditValue) => {
+                config.setting_edit =
+                    Some(config.setting_values[config.selected_setting()].clone());
+                config.status_message = None;
+            }
+            Some(Action::Save) => config.save_table_changes(),
+            Some(Action::SelectPrevious) => config.previous_table_entry(),
+            Some(Action::SelectNext) => config.next_table_entry(),
+            Some(Action::NextValue) => config.cycle_setting(true),
+            Some(Action::PreviousValue) => config.cycle_setting(false),
             _ => {}
         },
-        UiState::ConfigUi => match key.code {
-            KeyCode::Tab => config.next(),
-            KeyCode::Esc => config.clear_table_entry(),
-            KeyCode::Enter => config.save_table_changes(),
-            KeyCode::Up => config.previous_table_entry(),
-            KeyCode::Down => config.next_table_entry(),
-            KeyCode::Right => config.move_value_right(),
-            KeyCode::Left => config.move_value_left(),
+        Context::Statistics => match action {
+            Some(Action::NextRange) => config.stats_range = config.stats_range.next(),
+            Some(Action::PreviousRange) => config.stats_range = config.stats_range.previous(),
+            Some(Action::GroupByTag) => config.stats_by_tag = !config.stats_by_tag,
             _ => {}
         },
+        Context::Global => {}
     }
     Ok(())
 }
 
-pub fn ui<B: Backend>(f: &mut Frame<B>, config: &mut Configuration, input_field: &InputField) {
-    let mut size = f.size();
-    let block = Block::default().style(
-        Style::default()
-            .fg(get_foreground_color(config.darkmode))
-            .bg(get_background_color(config.darkmode)),
-    );
-    f.render_widget(block, size);
+/// Keys of the focus mode, they act on the timer it shows
+fn handle_focus_key(action: Option<Action>, config: &mut Configuration, pause_flag: &mut bool) {
+    let focused = focused_timer(config).map(|i| config.timers[i].id.to_string());
+    let modified = match (action, focused) {
+        (Some(Action::LeaveFocus), _) => {
+            config.focus_mode = false;
+            false
+        }
+        (Some(Action::PauseAll), _) => {
+            *pause_flag = !*pause_flag;
+            if *pause_flag {
+                count_global_pause(config);
+            }
+            false
+        }
+        (Some(Action::Pause), Some(id)) => {
+            toggle_pause_timer(&id, config);
+            true
+        }
+        (Some(Action::Skip), Some(id)) => {
+            skip_timer(&id, config);
+            true
+        }
+        (Some(Action::Acknowledge), Some(_)) => {
+            acknowledge_timers("", config);
+            true
+        }
+        _ => false,
+    };
+    if modified {
+        config.write_config_to_file().unwrap();
+        config.update_timers();
+    }
+}
+
+/// Typing the name of a new or renamed timer type in the Config tab
+fn handle_type_name_key(key: KeyEvent, action: Option<Action>, config: &mut Configuration) {
+    let Some(mut edit) = config.type_edit.take() else {
+        return;
+    };
+    match action {
+        Some(Action::DeleteChar) => {
+            edit.input_mut().pop();
+        }
+        Some(Action::Cancel) => {
+            config.status_message = None;
+            return;
+        }
+        Some(Action::Run) => {
+            let result = match &edit {
+                TypeEdit::New(input) => add_type(config, input, "Green"),
+                TypeEdit::Rename { name, input } => rename_type(config, name, input),
+            };
+            match result {
+                Ok(message) => {
+                    let name = edit.input().trim().to_lowercase();
+                    let position = type_names(config).iter().position(|n| *n == name);
+                    config.table_state_types.select(position);
+                    config.status_message = Some(message);
+                    config.write_config_to_file().unwrap();
+                    config.update_timers();
+                    return;
+                }
+                Err(e) => config.status_message = Some(e),
+            }
+        }
+        _ => {
+            if let KeyCode::Char(c) = key.code {
+                edit.input_mut().push(c);
+            }
+        }
+    }
+    config.type_edit = Some(edit);
+}
+
+/// Typing the value of the selected setting in the Config tab, it is checked when it is
+/// entered and stored with the other values when saving
+fn handle_setting_edit_key(key: KeyEvent, action: Option<Action>, config: &mut Configuration) {
+    let Some(mut input) = config.setting_edit.take() else {
+        return;
+    };
+    match action {
+        Some(Action::DeleteChar) => {
+            input.pop();
+        }
+        Some(Action::Cancel) => {
+            config.status_message = None;
+            return;
+        }
+        Some(Action::Run) => {
+            let i = config.selected_setting();
+            match SETTINGS[i].check(&input) {
+                Ok(value) => {
+                    config.setting_values[i] = value.to_string();
+                    config.status_message = None;
+                    return;
+                }
+                Err(e) => config.status_message = Some(e),
+            }
+        }
+        _ => {
+            if let KeyCode::Char(c) = key.code {
+                input.push(c);
+            }
+        }
+    }
+    config.setting_edit = Some(input);
+}
 
-    let len_right_view_timers = config.num_rightview_timers();
+/// Keys of the Timer Types table in the Config tab. Changes are saved right away.
+fn handle_timer_types_key(action: Option<Action>, config: &mut Configuration) {
+    let names = type_names(config);
+    let selected = config
+        .table_state_types
+        .selected()
+        .and_then(|i| names.get(i))
+        .cloned();
 
-    let left_view_timers: Vec<&Timer> = config.timers.iter().filter(|t| t.left_view).collect();
-    let right_view_timers: Vec<&Timer> = config.timers.iter().filter(|t| !t.left_view).collect();
+    config.status_message = None;
+    match action {
+        Some(Action::LeaveTypes) => config.types_focus = false,
+        Some(Action::SelectPrevious) if !names.is_empty() => {
+            let i = config.table_state_types.selected().unwrap_or(0);
+            config
+                .table_state_types
+                .select(Some(if i == 0 { names.len() - 1 } else { i - 1 }));
+        }
+        Some(Action::SelectNext) if !names.is_empty() => {
+            let i = config.table_state_types.selected().map_or(0, |i| i + 1);
+            config
+                .table_state_types
+                .select(Some(if i >= names.len() { 0 } else { i }));
+        }
+        Some(action @ (Action::NextValue | Action::PreviousValue)) => {
+            if let Some(name) = selected {
+                cycle_type_color(config, &name, action == Action::NextValue);
+                config.write_config_to_file().unwrap();
+            }
+        }
+        Some(Action::AddType) => config.type_edit = Some(TypeEdit::New(String::new())),
+        Some(Action::PickColor) => open_color_picker(config),
+        Some(Action::Rename) => {
+            if let Some(name) = selected {
+                config.type_edit = Some(TypeEdit::Rename {
+                    input: name.clone(),
+                    name,
+                });
+            }
+        }
+        Some(Action::Delete) => {
+            if let Some(name) = selected {
+                config.status_message = Some(remove_type(config, &name).unwrap_or_else(|e| e));
+                let len = type_names(config).len();
+                if config
+                    .table_state_types
+                    .selected()
+                    .is_some_and(|i| i >= len)
+                {
+                    config.table_state_types.select(len.checked_sub(1));
+                }
+                config.write_config_to_file().unwrap();
+                config.update_timers();
+            }
+        }
+        _ => {}
+    }
+}
 
-    let mut left_timer_gauge_value = Vec::new();
-    let mut right_timer_gauge_value = Vec::new();
+/// Opens the color picker for the selected color setting or timer type, starting at its color
+fn open_color_picker(config: &mut Configuration) {
+    let (target, current) = if config.types_focus {
+        let names = type_names(config);
+        let Some(name) = config
+            .table_state_types
+            .selected()
+            .and_then(|i| names.get(i))
+        else {
+            return;
+        };
+        (
+            ColorTarget::TimerType(name.clone()),
+            config.timer_colors[name].clone(),
+        )
+    } else {
+        let i = config.selected_setting();
+        if !matches!(SETTINGS[i].kind, SettingKind::Color { .. }) {
+            return;
+        }
+        (ColorTarget::Setting(i), config.setting_values[i].clone())
+    };
+    let color = AcceptedColors::parse(&current);
+    config.status_message = None;
+    config.color_picker = Some(ColorPicker {
+        target,
+        index: color.and_then(|c| c.palette_index()).unwrap_or(0),
+        hex: match color {
+            Some(rgb @ AcceptedColors::Rgb(..)) => rgb.to_string(),
+            _ => String::new(),
+        },
+    });
+}
 
-    let mut constraints_vec = Vec::new();
-    let mut constraints_vec2 = Vec::new();
+/// The keys move through the palette, the hex value key starts a hex value that is typed
+/// with the hex digits and Backspace, picking uses the hex value if there is one
+fn handle_color_picker_key(key: KeyEvent, action: Option<Action>, config: &mut Configuration) {
+    let Some(mut picker) = config.color_picker.take() else {
+        return;
+    };
+    match action {
+        Some(Action::Cancel) => {
+            config.status_message = None;
+            return;
+        }
+        Some(Action::PreviousValue) => picker.index = picker.index.wrapping_sub(1),
+        Some(Action::NextValue) => picker.index = picker.index.wrapping_add(1),
+        Some(Action::PreviousRow) => picker.index = picker.index.wrapping_sub(16),
+        Some(Action::NextRow) => picker.index = picker.index.wrapping_add(16),
+        Some(Action::HexValue) => picker.hex = "#".to_string(),
+        Some(Action::Pick) => {
+            let color = if picker.hex.is_empty() {
+                Some(AcceptedColors::from_index(picker.index))
+            } else {
+                AcceptedColors::parse(&picker.hex)
+            };
+            if let Some(color) = color {
+                apply_picked_color(config, picker.target, color);
+                return;
+            }
+            config.status_message = Some(format!("Invalid color '{}', use #rrggbb", picker.hex));
+        }
+        _ => match key.code {
+            KeyCode::Char(c) if c.is_ascii_hexdigit() && (1..7).contains(&picker.hex.len()) => {
+                picker.hex.push(c.to_ascii_lowercase());
+            }
+            KeyCode::Backspace => {
+                picker.hex.pop();
+            }
+            _ => {}
+        },
+    }
+    if matches!(
+        action,
+        Some(Action::PreviousValue | Action::NextValue | Action::PreviousRow | Action::NextRow)
+    ) {
+        picker.hex.clear();
+    }
+    config.color_picker = Some(picker);
+}
 
-    // 1 length constraint for the upper Tab text
-    constraints_vec.push(Constraint::Length(1));
+fn apply_picked_color(config: &mut Configuration, target: ColorTarget, color: AcceptedColors) {
+    let color = color.to_string();
+    match target {
+        ColorTarget::Setting(i) => config.setting_values[i] = color,
+        ColorTarget::TimerType(name) => {
+            config.status_message = set_type_color(config, &name, &color).ok();
+            config.write_config_to_file().unwrap();
+            return;
+        }
+    }
+    config.save_table_changes();
+}
 
-    for i in &left_view_timers {
-        if i.is_active {
-            constraints_vec.push(Constraint::Length(4));
-        } else {
-            constraints_vec.push(Constraint::Length(3));
+/// Translates mouse events into the same actions as the keyboard: clicking selects tabs,
+/// timers and table rows, dragging moves a timer and the wheel scrolls through the entries
+pub fn handle_mouse_event(
+    mouse: MouseEvent,
+    config: &mut Configuration,
+    input_field: &mut InputField,
+    pause_flag: &mut bool,
+) -> Result<(), io::Error> {
+    let (x, y) = (mouse.column, mouse.row);
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            if contains(config.tabs_area, x, y) {
+                if let Some(index) = tab_index_at(&config.titles, x - config.tabs_area.x) {
+                    config.index = index;
+                }
+                return Ok(());
+            }
+            match UiState::get_current_ui(config.index) {
+                UiState::TimerUi => {
+                    if let Some(i) = timer_at(config, x, y) {
+                        config.input_mode = InputMode::Normal;
+                        config.selected_timer = Some(i);
+                        config.dragged_timer = Some(config.timers[i].id);
+                    }
+                }
+                UiState::SetsUi | UiState::ConfigUi => {
+                    if let Some(row) = row_at(&config.table_rows, x, y) {
+                        config.select_table_entry(row);
+                    }
+                }
+                UiState::StatisticsUi => {}
+            }
+        }
+        MouseEventKind::Up(MouseButton::Left) => {
+            // the timer is looked up again, it may have moved or been removed while dragging
+            let dragged = config.dragged_timer.take();
+            if let Some(from) = dragged.and_then(|id| config.timers.iter().position(|t| t.id == id))
+            {
+                let target = timer_at(config, x, y);
+                let left_view = match target {
+                    Some(i) => config.timers[i].left_view,
+                    None => !contains(config.column_areas[1], x, y),
+                };
+                if target != Some(from) && config.is_sorted_view() {
+                    config.status_message = Some(sorted_view_message(config));
+                } else if target != Some(from) {
+                    config.drop_timer(from, target, left_view);
+                    config.write_config_to_file().unwrap();
+                    config.update_timers();
+                }
+            }
+        }
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            // the wheel presses the key of the next or previous entry, nothing while typing
+            let action = if mouse.kind == MouseEventKind::ScrollDown {
+                Action::SelectNext
+            } else {
+                Action::SelectPrevious
+            };
+            let context = key_context(config);
+            if let Some(key) = config.key_map.first_key(context, action) {
+                handle_key_press(key, config, input_field, pause_flag)?;
+            }
         }
+        _ => {}
+    }
+    Ok(())
+}
 
-        // find the % of elapsed time and push them to vec
-        let total_time = i.initial_time as i64;
-        let remaining_time = i.timeleft_secs as i64;
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height
+}
 
-        let completed_time = total_time - remaining_time;
+/// Index of the timer rendered at the given position
+fn sorted_view_message(config: &Configuration) -> String {
+    format!(
+        "Timers can only be moved in queue order, press {} to change the sorting",
+        config.key_map.keys(Context::Timer, Action::Sort)
+    )
+}
 
-        let percentage_completed = (completed_time as f64 / total_time as f64) * 100.0;
+fn timer_at(config: &Configuration, x: u16, y: u16) -> Option<usize> {
+    row_at(&config.timer_areas, x, y).filter(|&i| i < config.timers.len())
+}
 
-        left_timer_gauge_value.push(percentage_completed)
-    }
+/// Index of the entry whose recorded area contains the position
+fn row_at(areas: &[(usize, Rect)], x: u16, y: u16) -> Option<usize> {
+    areas
+        .iter()
+        .find(|(_, area)| contains(*area, x, y))
+        .map(|(i, _)| *i)
+}
 
-    // Constraints to control all the empty spaces below the timer and the input field.
-    // Min 0 so if no space, it won't have any size
-    // Max 3 so ensure the input field doesn't over extend
-    constraints_vec.push(Constraint::Min(0));
-    constraints_vec.push(Constraint::Max(3));
+/// The areas of the rows a `Table` with a one line header draws, every row followed by a
+/// margin of one line, starting at the offset of its state
+fn table_row_areas(area: Rect, offset: usize, heights: &[u16]) -> Vec<(usize, Rect)> {
+    let inner = Rect::new(
+        area.x + 1,
+        area.y + 3,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(4),
+    );
+    let mut rows = Vec::new();
+    let mut y = inner.y;
+    for (i, &height) in heights.iter().enumerate().skip(offset) {
+        if y + height > inner.bottom() {
+            break;
+        }
+        rows.push((i, Rect::new(inner.x, y, inner.width, height)));
+        y += height + 1;
+    }
+    rows
+}
 
-    if len_right_view_timers > 0 {
-        constraints_vec2.push(Constraint::Length(1));
+/// Index of the tab title at the given column, mirroring the layout of the `Tabs` widget:
+/// every title is padded by one space on both sides and followed by a one column divider
+fn tab_index_at(titles: &[&str], x: u16) -> Option<usize> {
+    let mut start = 0;
+    for (i, title) in titles.iter().enumerate() {
+        let end = start + title.chars().count() as u16 + 2;
+        if x < end {
+            return Some(i);
+        }
+        start = end + 1;
+    }
+    None
+}
 
-        for i in &right_view_timers {
-            if i.is_active {
-                constraints_vec2.push(Constraint::Length(4));
+/// Handles the single-key actions on the selected (or marked) timers in the Timer tab
+fn handle_timer_navigation(
+    action: Option<Action>,
+    config: &mut Configuration,
+    input_field: &mut InputField,
+    pause_flag: &mut bool,
+) {
+    let scroll_help = |config: &mut Configuration, lines: i32| {
+        config.help_scroll = (config.help_scroll as i32 + lines).max(0) as u16;
+    };
+    let modified = match action {
+        Some(Action::SelectNext) if config.show_popup => {
+            scroll_help(config, 1);
+            false
+        }
+        Some(Action::SelectPrevious) if config.show_popup => {
+            scroll_help(config, -1);
+            false
+        }
+        Some(Action::PageDown) if config.show_popup => {
+            scroll_help(config, 10);
+            false
+        }
+        Some(Action::PageUp) if config.show_popup => {
+            scroll_help(config, -10);
+            false
+        }
+        Some(Action::Command) => {
+            config.input_mode = InputMode::Command;
+            config.status_message = None;
+            false
+        }
+        Some(Action::ToggleHelp) => {
+            config.show_popup = !config.show_popup;
+            config.help_scroll = 0;
+            false
+        }
+        Some(Action::BigDigits) => {
+            config.big_digits = !config.big_digits;
+            config.sync_setting("big_digits");
+            true
+        }
+        Some(Action::FocusMode) => {
+            config.focus_mode = true;
+            false
+        }
+        Some(Action::TableView) => {
+            config.table_view = !config.table_view;
+            config.sync_setting("table_view");
+            config.column_offsets = [0, 0];
+            true
+        }
+        Some(Action::Sort) if config.table_view => {
+            config.table_sort = config.table_sort.next();
+            false
+        }
+        Some(Action::ReverseSort) if config.table_view => {
+            config.table_sort_descending = !config.table_sort_descending;
+            false
+        }
+        Some(Action::Search) => {
+            config.input_mode = InputMode::Search;
+            config.status_message = None;
+            false
+        }
+        Some(Action::NextMatch) => {
+            config.select_match(true);
+            false
+        }
+        Some(Action::PreviousMatch) => {
+            config.select_match(false);
+            false
+        }
+        Some(Action::PauseAll) => {
+            *pause_flag = !*pause_flag;
+            if *pause_flag {
+                count_global_pause(config);
+            }
+            false
+        }
+        Some(Action::SelectNext) => {
+            config.select_next_timer();
+            false
+        }
+        Some(Action::SelectPrevious) => {
+            config.select_previous_timer();
+            false
+        }
+        Some(Action::PageDown) => {
+            config.select_page(true);
+            false
+        }
+        Some(Action::PageUp) => {
+            config.select_page(false);
+            false
+        }
+        Some(Action::OtherColumn) => {
+            config.select_other_column();
+            false
+        }
+        Some(Action::Mark) => {
+            if let Some(timer) = config.selected_timer.and_then(|i| config.timers.get_mut(i)) {
+                timer.marked = !timer.marked;
+            }
+            false
+        }
+        Some(Action::Clear) => {
+            if config.show_popup {
+                config.show_popup = false;
+            } else if !config.search_query.is_empty() {
+                config.search_query.clear();
             } else {
-                constraints_vec2.push(Constraint::Length(3));
+                config.timers.iter_mut().for_each(|t| t.marked = false);
+            }
+            false
+        }
+        Some(Action::Rename) => {
+            prefill_command(config, input_field, "rename");
+            false
+        }
+        Some(Action::ToggleChecklist) => {
+            if let Some(timer) = config.selected_timer.and_then(|i| config.timers.get_mut(i)) {
+                timer.expanded = !timer.expanded;
+            }
+            false
+        }
+        Some(Action::AddSubtask) => {
+            prefill_command(config, input_field, "sub add");
+            false
+        }
+        Some(Action::CheckSubtask) => {
+            prefill_command(config, input_field, "sub check");
+            false
+        }
+        Some(Action::MoveDown | Action::MoveUp) if config.is_sorted_view() => {
+            config.status_message = Some(sorted_view_message(config));
+            false
+        }
+        Some(Action::MoveDown) => {
+            config.move_selected_timer(false);
+            true
+        }
+        Some(Action::MoveUp) => {
+            config.move_selected_timer(true);
+            true
+        }
+        Some(Action::Delete) => {
+            let ids = config.target_timer_ids();
+            remove_timers(&ids, config);
+            true
+        }
+        Some(action @ (Action::Increase | Action::Decrease)) => {
+            let step = config.adjust_step.to_string();
+            for id in config.target_timer_ids() {
+                if action == Action::Increase {
+                    increase_timer(&id.to_string(), &step, config);
+                } else {
+                    decrease_timer(&id.to_string(), &step, config);
+                }
+            }
+            true
+        }
+        Some(Action::Pause) => {
+            for id in config.target_timer_ids() {
+                toggle_pause_timer(&id.to_string(), config);
+            }
+            true
+        }
+        Some(Action::Skip) => {
+            for id in config.target_timer_ids() {
+                skip_timer(&id.to_string(), config);
             }
+            true
+        }
+        Some(Action::Acknowledge) => {
+            acknowledge_timers("", config);
+            true
+        }
+        Some(Action::Snooze) => {
+            // the selected timer if it has finished, otherwise the one that finished last
+            let id = config
+                .selected_timer
+                .and_then(|i| config.timers.get(i))
+                .filter(|t| t.timeleft_secs == 0)
+                .map_or(String::new(), |t| t.id.to_string());
+            snooze_timer(&id, "", config);
+            true
+        }
+        _ => false,
+    };
+    if modified {
+        config.write_config_to_file().unwrap();
+        config.update_timers();
+    }
+}
 
-            // find the % of elapsed time and push them to vec
-            let total_time = i.initial_time as i64;
-            let remaining_time = i.timeleft_secs as i64;
+/// Starts a command for the selected timer, e.g. "rename 3 ", that the user completes
+fn prefill_command(config: &mut Configuration, input_field: &mut InputField, command: &str) {
+    if let Some(timer) = config.selected_timer.and_then(|i| config.timers.get(i)) {
+        input_field.content = format!("{} {} ", command, timer.id);
+        input_field.cursor_position = input_field.content.chars().count();
+        config.input_mode = InputMode::Command;
+    }
+}
 
-            let completed_time = total_time - remaining_time;
+/// Below this size the tabs can't be drawn in a usable way
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+/// Below this width the two timer columns are stacked and the side panels go below
+const STACK_WIDTH: u16 = 80;
 
-            let percentage_completed = (completed_time as f64 / total_time as f64) * 100.0;
+pub fn ui<B: Backend>(f: &mut Frame<B>, config: &mut Configuration, input_field: &InputField) {
+    let size = f.size();
+    let palette = config.palette();
+    let block = Block::default().style(
+        Style::default()
+            .fg(config.foreground())
+            .bg(config.background()),
+    );
+    f.render_widget(block, size);
+    config.timer_areas.clear();
 
-            right_timer_gauge_value.push(percentage_completed)
-        }
-        // Constraints to control all the empty spaces below the timer2 and the input field.
-        // Min 0 so if no space, it won't have any size
-        // Max 3 so ensure the input field doesn't over extend
-        constraints_vec2.push(Constraint::Min(0));
-        constraints_vec2.push(Constraint::Max(3));
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        // nothing can be clicked on this screen
+        config.tabs_area = Rect::default();
+        config.table_rows.clear();
+        config.column_areas = [Rect::default(); 2];
+        let text = format!(
+            "Terminal too small: {}x{}, at least {}x{} is needed",
+            size.width, size.height, MIN_WIDTH, MIN_HEIGHT
+        );
+        let paragraph = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        let y = size.height.saturating_sub(2) / 2;
+        f.render_widget(
+            paragraph,
+            Rect::new(size.x, size.y + y, size.width, size.height - y),
+        );
+        return;
     }
 
     let titles = config
@@ -183,14 +822,14 @@ pub fn ui<B: Backend>(f: &mut Frame<B>, config: &mut Configuration, input_field:
                 Span::styled(
                     first,
                     Style::default()
-                        .fg(get_foreground_color(config.darkmode))
-                        .bg(get_background_color(config.darkmode)),
+                        .fg(palette.foreground)
+                        .bg(palette.background),
                 ),
                 Span::styled(
                     rest,
                     Style::default()
-                        .fg(get_foreground_color(config.darkmode))
-                        .bg(get_background_color(config.darkmode)),
+                        .fg(palette.foreground)
+                        .bg(palette.background),
                 ),
             ])
         })
@@ -198,241 +837,177 @@ pub fn ui<B: Backend>(f: &mut Frame<B>, config: &mut Configuration, input_field:
 
     let tabs = Tabs::new(titles)
         .select(config.index)
-        .style(
-            Style::default()
-                .fg(Color::Gray)
-                .bg(get_background_color(config.darkmode)),
-        )
+        .style(Style::default().fg(palette.tabs).bg(palette.background))
         .highlight_style(
             Style::default()
                 .add_modifier(Modifier::BOLD)
-                .bg(AcceptedColors::from_str(&config.activecolor)
-                    .unwrap()
-                    .to_color()),
+                .bg(palette.tab_highlight),
         );
 
-    let chunks_index1 = Layout::default()
+    if config.focus_mode {
+        // only the focused timer can be clicked on this screen
+        config.tabs_area = Rect::default();
+        config.table_rows.clear();
+        config.column_areas = [Rect::default(); 2];
+        focus_rendering(f, config, size);
+        return;
+    }
+
+    // 1 line for the upper Tab text, the table and 2 lines for the hints below
+    let table_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Percentage(3), Constraint::Percentage(80)])
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
         .split(size)
         .to_vec();
 
-    let mut chunks2 = Vec::new();
-    if len_right_view_timers > 0 {
-        let mut size2 = size;
-        size2.x = size.width / 2;
-        size2.width /= 2;
-        chunks2 = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(constraints_vec2)
-            .split(size2)
-            .to_vec();
-    }
-
-    if len_right_view_timers > 0 {
-        size.width /= 2;
-    }
+    // 1 line for the upper Tab text, the timer columns and 3 lines for the input field
     let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(&*constraints_vec)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
         .split(size);
 
     f.render_widget(tabs, chunks[0]);
+    config.tabs_area = chunks[0];
 
     if config.index == 0 {
-        // loop for timers
-        // len -2 because last 2 are used for rendering the empty fields and the input field
-        for i in 1..chunks.len() - 2 {
-            let current_timer = left_view_timers[i - 1];
-
-            let current_timer_color =
-                if current_timer.timer_type.is_some() && current_timer.is_active {
-                    AcceptedColors::from_str(current_timer.timer_type.as_ref().unwrap())
-                        .unwrap()
-                        .to_color()
-                } else if current_timer.is_active {
-                    AcceptedColors::from_str(&config.activecolor)
-                        .unwrap()
-                        .to_color()
-                } else {
-                    Color::DarkGray
-                };
-
-            let mut paragraph = Paragraph::new(current_timer.formatted())
-                .block(Block::default().borders(Borders::ALL))
-                .style(
-                    Style::default()
-                        .fg(current_timer_color)
-                        .bg(get_background_color(config.darkmode)),
-                );
-
-            // if the timer is not active, only render the text on the entire chunk
-            // otherwise divide the chunk into 2 smaller chunks and render text + gauge
-            if !current_timer.is_active {
-                f.render_widget(paragraph, chunks[i]);
-            } else {
-                paragraph = paragraph
-                    .block(Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT));
-                let gauge_label = format!("{:.2}%", left_timer_gauge_value[i - 1]);
-                let timer_gauge = Gauge::default()
-                    .block(
-                        Block::default()
-                            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-                            .border_style(
-                                Style::default()
-                                    .fg(current_timer_color)
-                                    .bg(get_background_color(config.darkmode)),
-                            ),
-                    )
-                    .gauge_style(
-                        Style::default()
-                            .fg(current_timer_color)
-                            .bg(get_background_color(config.darkmode))
-                            .add_modifier(Modifier::ITALIC),
-                    )
-                    .label(gauge_label)
-                    .ratio(left_timer_gauge_value[i - 1] / 100.0)
-                    .use_unicode(true);
-
-                let divided_chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                    .split(chunks[i]);
-
-                f.render_widget(paragraph, divided_chunks[0]);
-                f.render_widget(timer_gauge, divided_chunks[1]);
-            }
-        }
-
-        // Renders the empty spaces below the timer with nothing
-        f.render_widget(Paragraph::new(""), chunks[chunks.len() - 2]);
-
-        if len_right_view_timers > 0 {
-            size.width *= 2;
-        }
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(constraints_vec)
-            .split(size)
-            .to_vec();
-
-        timertab_rendering(
-            len_right_view_timers,
-            chunks2,
-            right_view_timers,
-            f,
-            input_field,
-            &chunks,
-            config,
-            right_timer_gauge_value,
-            size,
-        );
+        timertab_rendering(f, config, input_field, &chunks, size);
     } else if config.index == 1 {
-        setstab_rendering(config, f, chunks_index1);
-    } 
-    else if config.index == 2 {
-        configtab_rendering(config, f, chunks_index1);
+        setstab_rendering(config, f, table_chunks);
+    } else if config.index == 2 {
+        configtab_rendering(config, f, table_chunks);
+    } else if config.index == STATISTICS_TAB {
+        let area = Rect::new(
+            size.x,
+            size.y + 1,
+            size.width,
+            size.height.saturating_sub(1),
+        );
+        config.history.refresh();
+        statisticstab_rendering(config, f, area);
     }
 }
 
 pub fn timertab_rendering<B: Backend>(
-    len_right_view_timers: usize,
-    chunks2: Vec<Rect>,
-    right_view_timers: Vec<&Timer>,
     f: &mut Frame<B>,
+    config: &mut Configuration,
     input_field: &InputField,
-    chunks: &Vec<Rect>,
-    config: &Configuration,
-    right_timer_gauge_value: Vec<f64>,
+    chunks: &[Rect],
     size: Rect,
 ) {
-    if len_right_view_timers > 0 {
-        // loop for timers2
-        // len -2 because last 2 are used for rendering the empty fields and the input field
-        for i in 1..chunks2.len() - 2 {
-            let current_timer = right_view_timers[i - 1];
-            let current_timer_color =
-                if current_timer.timer_type.is_some() && current_timer.is_active {
-                    AcceptedColors::from_str(current_timer.timer_type.as_ref().unwrap())
-                        .unwrap()
-                        .to_color()
-                } else if current_timer.is_active {
-                    AcceptedColors::from_str(&config.activecolor)
-                        .unwrap()
-                        .to_color()
-                } else {
-                    Color::DarkGray
-                };
-
-            let mut paragraph = Paragraph::new(current_timer.formatted())
-                .block(Block::default().borders(Borders::ALL))
-                .style(
-                    Style::default()
-                        .fg(current_timer_color)
-                        .bg(get_background_color(config.darkmode)),
-                );
-
-            // if the timer is not active, only render the text on the entire chunk
-            // otherwise divide the chunk into 2 smaller chunks and render text + gauge
-            if !current_timer.is_active {
-                f.render_widget(paragraph, chunks2[i]);
-            } else {
-                paragraph = paragraph
-                    .block(Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT));
-                let gauge_label = format!("{:.2}%", right_timer_gauge_value[i - 1]);
-                let timer_gauge = Gauge::default()
-                    .block(
-                        Block::default()
-                            .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
-                            .border_style(
-                                Style::default()
-                                    .fg(current_timer_color)
-                                    .bg(get_background_color(config.darkmode)),
-                            ),
-                    )
-                    .gauge_style(
-                        Style::default()
-                            .fg(current_timer_color)
-                            .bg(get_background_color(config.darkmode))
-                            .add_modifier(Modifier::ITALIC),
-                    )
-                    .label(gauge_label)
-                    .ratio(right_timer_gauge_value[i - 1] / 100.0)
-                    .use_unicode(true);
-
-                let divided_chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                    .split(chunks2[i]);
-
-                f.render_widget(paragraph, divided_chunks[0]);
-                f.render_widget(timer_gauge, divided_chunks[1]);
-            }
+    let mut timer_area = chunks[1];
+    let mut filters = Vec::new();
+    if let Some(tag) = &config.tag_filter {
+        filters.push(format!("Filter #{} (run 'filter' to show all)", tag));
+    }
+    if !config.search_query.is_empty() {
+        filters.push(format!(
+            "Search '{}' ({}/{}: next/previous match, {}: clear)",
+            config.search_query,
+            config.key_map.keys(Context::Timer, Action::NextMatch),
+            config.key_map.keys(Context::Timer, Action::PreviousMatch),
+            config.key_map.keys(Context::Timer, Action::Clear)
+        ));
+    }
+    if !filters.is_empty() {
+        let shown = config
+            .timers
+            .iter()
+            .filter(|t| config.is_visible(t))
+            .count();
+        let indicator = Paragraph::new(Span::styled(
+            format!(
+                "{}: {} of {} timers shown",
+                filters.join(", "),
+                shown,
+                config.timers.len()
+            ),
+            Style::default()
+                .fg(config.background())
+                .bg(config.active_color()),
+        ));
+        f.render_widget(
+            indicator,
+            Rect::new(timer_area.x, timer_area.y, timer_area.width, 1),
+        );
+        timer_area.y += 1;
+        timer_area.height = timer_area.height.saturating_sub(1);
+    }
+    config.column_areas = if config.visible_column_indices(false).is_empty() {
+        [timer_area, Rect::default()]
+    } else {
+        let direction = if timer_area.width < STACK_WIDTH {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        let columns = Layout::default()
+            .direction(direction)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(timer_area);
+        [columns[0], columns[1]]
+    };
+    for (area, left_view) in config.column_areas.into_iter().zip([true, false]) {
+        if area.height > 0 {
+            timer_column_rendering(f, config, area, left_view);
         }
-        // Renders the empty spaces below the timer with nothing
-        f.render_widget(Paragraph::new(""), chunks2[chunks2.len() - 2]);
     }
 
-    let input = Paragraph::new(&*input_field.content)
+    let (input_text, input_title) = if config.input_mode == InputMode::Search {
+        (format!("/{}", config.search_query), "Search")
+    } else {
+        (input_field.content.clone(), "Input")
+    };
+    let input = Paragraph::new(input_text)
         .style(
             Style::default()
-                .fg(AcceptedColors::from_str(&config.activecolor)
-                    .unwrap()
-                    .to_color())
-                .bg(get_background_color(config.darkmode)),
+                .fg(config.palette().input)
+                .bg(config.background()),
+        )
+        .block(bordered_block(config).title(input_title));
+    if config.input_mode == InputMode::Command {
+        f.set_cursor(
+            chunks[2].x + input_field.cursor_position as u16 + 1,
+            chunks[2].y + 1,
+        );
+    } else if config.input_mode == InputMode::Search {
+        f.set_cursor(
+            chunks[2].x + config.search_query.chars().count() as u16 + 2,
+            chunks[2].y + 1,
+        );
+    }
+    f.render_widget(input, chunks[2]);
+    let keys = |context, action| config.key_map.keys(context, action);
+    let text = if let Some(message) = &config.status_message {
+        message.clone()
+    } else if config.input_mode == InputMode::Command {
+        format!(
+            "Press {} to run the command; Press {} to clear the input field",
+            keys(Context::Input, Action::Run),
+            keys(Context::Input, Action::Cancel)
+        )
+    } else if config.input_mode == InputMode::Search {
+        format!(
+            "Type to filter the timers; {} to keep the filter; {} to clear it",
+            keys(Context::Input, Action::Run),
+            keys(Context::Input, Action::Cancel)
         )
-        .block(Block::default().borders(Borders::ALL).title("Input"));
-    f.set_cursor(
-        chunks[0].x + input_field.cursor_position as u16 + 1,
-        chunks[chunks.len() - 1].y + 1,
-    );
-    f.render_widget(input, chunks[chunks.len() - 1]);
-    let text = if !input_field.content.is_empty() {
-        "Press <ESC> to clear the input field"
-    } else if config.show_popup {
-        "Press <SPACE> to pause the timers; Press h to close the help-popup; Press q to quit the application"
     } else {
-        "Press <SPACE> to pause the timers; Press h to show the help-popup; Press q to quit the application"
+        format!(
+            "Press {} to enter a command; {} to pause the timers; {} to {} the help-popup; {} to quit",
+            keys(Context::Timer, Action::Command),
+            keys(Context::Timer, Action::PauseAll),
+            keys(Context::Timer, Action::ToggleHelp),
+            if config.show_popup { "close" } else { "show" },
+            keys(Context::Global, Action::Quit)
+        )
     };
     let paragraph = Paragraph::new(Span::styled(
         text,
@@ -440,23 +1015,466 @@ pub fn timertab_rendering<B: Backend>(
     ))
     .alignment(Alignment::Center)
     .wrap(Wrap { trim: true });
-    f.render_widget(paragraph, chunks[chunks.len() - 1]);
+    f.render_widget(paragraph, chunks[2]);
     if config.show_popup {
+        // the commands, followed by the keys in effect
         let helptext = fs::read_to_string("helptext.txt").expect("Unable to read helptext file");
-        let paragraph = Paragraph::new(helptext)
-            .block(Block::default().borders(Borders::ALL))
+        let mut lines: Vec<Line> = helptext.lines().map(Line::from).collect();
+        lines.push(Line::from(""));
+        lines.extend(config.key_map.help_lines().into_iter().map(Line::from));
+        // small terminals need all the room for the help
+        let area = if size.width < STACK_WIDTH || size.height < 30 {
+            size
+        } else {
+            centered_rect(80, 50, size)
+        };
+        let max_scroll = (lines.len() as u16).saturating_sub(area.height.saturating_sub(2));
+        config.help_scroll = config.help_scroll.min(max_scroll);
+        let paragraph = Paragraph::new(lines)
+            .scroll((config.help_scroll, 0))
+            .block(bordered_block(config))
             .style(
                 Style::default()
-                    .fg(Color::LightRed)
-                    .bg(get_background_color(config.darkmode)),
+                    .fg(config.palette().popup)
+                    .bg(config.background()),
             );
-        let area = centered_rect(80, 50, size);
         f.render_widget(Clear, area); //this clears out the background
         f.render_widget(paragraph, area);
     }
 }
 
-pub fn setstab_rendering<B: Backend>(
+/// Renders the timers of one column as a scrollable list that keeps the active and the
+/// selected timer in view and shows how many timers are hidden above and below
+fn timer_column_rendering<B: Backend>(
+    f: &mut Frame<B>,
+    config: &mut Configuration,
+    area: Rect,
+    left_view: bool,
+) {
+    let column = config.display_column_indices(left_view);
+    let column_index = if left_view { 0 } else { 1 };
+    let mut area = area;
+    let running = config
+        .running_timer_indices()
+        .into_iter()
+        .find(|&i| config.timers[i].left_view == left_view && !config.timers[i].awaiting_ack);
+    if let Some(active) = running {
+        if config.big_digits && area.height >= 12 {
+            let height = (area.height * 2 / 5).max(4);
+            big_digits_rendering(f, config, &config.timers[active], Rect { height, ..area });
+            area.y += height;
+            area.height -= height;
+        }
+    }
+    if config.table_view {
+        timer_table_rendering(f, config, &column, area, left_view);
+        return;
+    }
+    let heights: Vec<u16> = column
+        .iter()
+        .map(|&i| timer_height(&config.timers[i]))
+        .collect();
+
+    let mut offset = config.column_offsets[column_index].min(column.len().saturating_sub(1));
+    if let Some(active) = column
+        .iter()
+        .position(|&i| config.timers[i].is_active || config.timers[i].awaiting_ack)
+    {
+        offset = scroll_into_view(&heights, offset, area.height, active);
+    }
+    if let Some(selected) = config
+        .selected_timer
+        .and_then(|s| column.iter().position(|&i| i == s))
+    {
+        offset = scroll_into_view(&heights, offset, area.height, selected);
+    }
+    let end = visible_end(&heights, offset, area.height);
+    config.column_offsets[column_index] = offset;
+    config.column_page_sizes[column_index] = (end - offset).max(1);
+
+    let mut y = area.y;
+    if offset > 0 {
+        f.render_widget(
+            scroll_indicator(config, format!("▲ {} more above", offset)),
+            Rect::new(area.x, y, area.width, 1),
+        );
+        y += 1;
+    }
+    for (&i, &height) in column[offset..end].iter().zip(&heights[offset..end]) {
+        let timer_area = Rect::new(area.x, y, area.width, height);
+        timer_rendering(f, config, &config.timers[i], timer_area);
+        config.timer_areas.push((i, timer_area));
+        y += height;
+    }
+    if end < column.len() {
+        f.render_widget(
+            scroll_indicator(config, format!("▼ {} more below", column.len() - end)),
+            Rect::new(area.x, area.y + area.height - 1, area.width, 1),
+        );
+    }
+}
+
+/// Renders the timers of one column as a table with one row per timer, scrolled so that the
+/// active and the selected timer are visible. The active timer gets a small progress bar.
+fn timer_table_rendering<B: Backend>(
+    f: &mut Frame<B>,
+    config: &mut Configuration,
+    column: &[usize],
+    area: Rect,
+    left_view: bool,
+) {
+    let column_index = if left_view { 0 } else { 1 };
+    // borders and header
+    let page = area.height.saturating_sub(3).max(1) as usize;
+    let mut offset = config.column_offsets[column_index].min(column.len().saturating_sub(1));
+    let active = column
+        .iter()
+        .position(|&i| config.timers[i].is_active || config.timers[i].awaiting_ack);
+    let selected = config
+        .selected_timer
+        .and_then(|s| column.iter().position(|&i| i == s));
+    for index in [active, selected].into_iter().flatten() {
+        if index < offset {
+            offset = index;
+        } else if index >= offset + page {
+            offset = index + 1 - page;
+        }
+    }
+    let end = (offset + page).min(column.len());
+    config.column_offsets[column_index] = offset;
+    config.column_page_sizes[column_index] = page;
+
+    let header_cells = [
+        SortColumn::Id,
+        SortColumn::Remaining,
+        SortColumn::EndTime,
+        SortColumn::Type,
+        SortColumn::Description,
+        SortColumn::Repeats,
+    ]
+    .iter()
+    .map(|&sort_column| {
+        let cell = Cell::from(sort_column.to_string());
+        if sort_column == config.table_sort {
+            cell.style(Style::default().add_modifier(Modifier::UNDERLINED))
+        } else {
+            cell
+        }
+    });
+    let header = Row::new(header_cells).style(
+        Style::default()
+            .fg(config.foreground())
+            .add_modifier(Modifier::BOLD),
+    );
+    let type_width = column[offset..end]
+        .iter()
+        .filter_map(|&i| config.timers[i].timer_type.as_ref())
+        .map(|t| t.chars().count() as u16)
+        .max()
+        .unwrap_or(0)
+        .max(4);
+
+    let mut widths = [3, 8 + 1 + MINI_GAUGE_WIDTH, 5, type_width, 0, 7];
+    // the description takes the rest of the width without the borders and the column spacing
+    widths[4] = area
+        .width
+        .saturating_sub(2 + widths.len() as u16 - 1 + widths.iter().sum::<u16>())
+        .max(8);
+    let rows: Vec<Row> = column[offset..end]
+        .iter()
+        .map(|&i| timer_table_row(config, i, widths[4] as usize))
+        .collect();
+
+    let mut title = if left_view { "Left" } else { "Right" }.to_string();
+    if offset > 0 || end < column.len() {
+        title = format!("{} {}-{} of {}", title, offset + 1, end, column.len());
+    }
+    if config.is_sorted_view() {
+        let order = if config.table_sort_descending {
+            "▼"
+        } else {
+            "▲"
+        };
+        title = format!("{}, sorted by {} {}", title, config.table_sort, order);
+    }
+    let widths = widths.map(Constraint::Length);
+    let table = Table::new(rows)
+        .header(header)
+        .block(bordered_block(config).title(title))
+        .style(Style::default().bg(config.background()))
+        .widths(&widths);
+    f.render_widget(table, area);
+    for (row, &i) in column[offset..end].iter().enumerate() {
+        let row_area = Rect::new(area.x + 1, area.y + 2 + row as u16, area.width - 2, 1);
+        config.timer_areas.push((i, row_area));
+    }
+}
+
+const MINI_GAUGE_WIDTH: u16 = 5;
+
+/// A row of the table view in the color of the timer, reversed if the timer is selected
+fn timer_table_row<'a>(config: &Configuration, index: usize, description_width: usize) -> Row<'a> {
+    let timer = &config.timers[index];
+    let (remaining, style) = if timer.awaiting_ack {
+        (
+            format!("+{}", format_clock(timer.overtime_secs())),
+            Style::default()
+                .fg(config.background())
+                .bg(config.active_color()),
+        )
+    } else if timer.in_overtime {
+        (
+            format!("+{}", format_clock(timer.overtime_secs())),
+            Style::default().fg(config.palette().finished),
+        )
+    } else if timer.is_active {
+        (
+            format!(
+                "{} {}",
+                format_clock(timer.timeleft_secs),
+                mini_gauge(timer, MINI_GAUGE_WIDTH as usize)
+            ),
+            Style::default().fg(timer_color(config, timer)),
+        )
+    } else {
+        (
+            format_clock(timer.timeleft_secs),
+            Style::default().fg(timer_color(config, timer)),
+        )
+    };
+    let end_time = if timer.timeleft_secs == 0 {
+        String::new()
+    } else {
+        timer.endtime.format("%H:%M").to_string()
+    };
+    let mut description = timer.description_with_tags();
+    if timer.paused {
+        description = format!("[paused] {}", description);
+    }
+    if timer.marked {
+        description = format!("* {}", description);
+    }
+    let repeats = if timer.repeat_times > 0 {
+        timer.repeat_times.to_string()
+    } else {
+        String::new()
+    };
+    let style = if config.input_mode != InputMode::Command && config.selected_timer == Some(index) {
+        style.add_modifier(Modifier::REVERSED | Modifier::BOLD)
+    } else {
+        style
+    };
+    Row::new(vec![
+        timer.id.to_string(),
+        remaining,
+        end_time,
+        timer.timer_type.clone().unwrap_or_default(),
+        truncate(&description, description_width),
+        repeats,
+    ])
+    .style(style)
+}
+
+/// Elapsed part of the timer as a bar of eighth blocks, e.g. "███▍  "
+fn mini_gauge(timer: &Timer, width: usize) -> String {
+    let elapsed = timer.initial_time - timer.timeleft_secs.min(timer.initial_time);
+    let eighths = (elapsed * 8 * width as u64)
+        .checked_div(timer.initial_time)
+        .unwrap_or(8 * width as u64) as usize;
+    let partial = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"][eighths % 8];
+    let bar = format!("{}{}", "█".repeat(eighths / 8), partial);
+    format!("{:<width$}", bar, width = width)
+}
+
+/// The remaining time of the active timer of a column in digits as big as the area allows
+fn big_digits_rendering<B: Backend>(
+    f: &mut Frame<B>,
+    config: &Configuration,
+    timer: &Timer,
+    area: Rect,
+) {
+    let block = bordered_block(config).title(format!(
+        " {} ",
+        truncate(&timer.description, inner_width(area).saturating_sub(2))
+    ));
+    let inner = block.inner(area);
+    let digits = big_digits::render(
+        &countdown_text(timer.timeleft_secs),
+        inner.width,
+        inner.height,
+    );
+    let padding = (inner.height as usize).saturating_sub(digits.len()) / 2;
+    let lines: Vec<Line> = std::iter::repeat_n(String::new(), padding)
+        .chain(digits)
+        .map(Line::from)
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .block(block)
+        .alignment(Alignment::Center)
+        .style(
+            Style::default()
+                .fg(running_color(config, timer))
+                .bg(config.background()),
+        );
+    f.render_widget(paragraph, area);
+}
+
+/// An active timer has 1 more line for its gauge, a waiting one for its overtime and an
+/// expanded one for each item of its checklist
+fn timer_height(timer: &Timer) -> u16 {
+    if timer.awaiting_ack {
+        4
+    } else if timer.in_overtime {
+        3
+    } else {
+        let checklist = if timer.expanded {
+            timer.subtasks.len() as u16
+        } else {
+            0
+        };
+        if timer.is_active {
+            4 + checklist
+        } else {
+            3 + checklist
+        }
+    }
+}
+
+/// Index after the last timer that fits into `height` when the list starts at `offset`,
+/// leaving room for the "more above" and "more below" lines
+fn visible_end(heights: &[u16], offset: usize, height: u16) -> usize {
+    let mut available = height.saturating_sub(if offset > 0 { 1 } else { 0 });
+    let mut end = offset;
+    while end < heights.len() {
+        let below = if end + 1 < heights.len() { 1 } else { 0 };
+        if heights[end] + below > available {
+            break;
+        }
+        available -= heights[end];
+        end += 1;
+    }
+    end
+}
+
+/// Offset of the list so that the timer at `index` is visible
+fn scroll_into_view(heights: &[u16], offset: usize, height: u16, index: usize) -> usize {
+    if index < offset {
+        return index;
+    }
+    let mut offset = offset;
+    while offset < index && visible_end(heights, offset, height) <= index {
+        offset += 1;
+    }
+    offset
+}
+
+fn scroll_indicator<'a>(config: &Configuration, text: String) -> Paragraph<'a> {
+    Paragraph::new(Span::styled(
+        text,
+        Style::default()
+            .fg(config.palette().inactive)
+            .add_modifier(Modifier::ITALIC),
+    ))
+    .alignment(Alignment::Center)
+}
+
+/// Renders a timer as a bordered paragraph; an active timer gets an additional gauge
+fn timer_rendering<B: Backend>(
+    f: &mut Frame<B>,
+    config: &Configuration,
+    timer: &Timer,
+    area: Rect,
+) {
+    if timer.awaiting_ack {
+        waiting_timer_rendering(f, config, timer, area);
+        return;
+    }
+    if timer.in_overtime {
+        // the overtime stays visible, the text before it is shortened
+        let overtime = format!("     overtime +{}", format_clock(timer.overtime_secs()));
+        let width = inner_width(area).saturating_sub(overtime.chars().count());
+        let paragraph = Paragraph::new(truncate(&timer_text(timer), width) + &overtime)
+            .block(timer_block(config, timer, Borders::ALL))
+            .style(
+                Style::default()
+                    .fg(config.palette().finished)
+                    .bg(config.background())
+                    .add_modifier(Modifier::BOLD),
+            );
+        f.render_widget(paragraph, area);
+        return;
+    }
+    let current_timer_color = timer_color(config, timer);
+
+    let width = inner_width(area);
+    let mut text = vec![Line::from(truncate(&timer_text(timer), width))];
+    if timer.expanded {
+        text.extend(timer.subtasks.iter().enumerate().map(|(i, subtask)| {
+            Line::from(truncate(
+                &format!(
+                    "  {}. [{}] {}",
+                    i + 1,
+                    if subtask.done { "x" } else { " " },
+                    subtask.description
+                ),
+                width,
+            ))
+        }));
+    }
+    let mut paragraph = Paragraph::new(text)
+        .block(timer_block(config, timer, Borders::ALL))
+        .style(
+            Style::default()
+                .fg(current_timer_color)
+                .bg(config.background()),
+        );
+
+    // if the timer is not active, only render the text on the entire chunk
+    // otherwise divide the chunk into 2 smaller chunks and render text + gauge
+    if !timer.is_active {
+        f.render_widget(paragraph, area);
+        return;
+    }
+    paragraph = paragraph.block(timer_block(
+        config,
+        timer,
+        Borders::TOP | Borders::LEFT | Borders::RIGHT,
+    ));
+    let percentage_completed = elapsed_percentage(timer);
+    let gauge_label = format!("{:.2}%", percentage_completed);
+    let timer_gauge = Gauge::default()
+        .block(
+            timer_block(
+                config,
+                timer,
+                Borders::BOTTOM | Borders::LEFT | Borders::RIGHT,
+            )
+            .border_style(
+                Style::default()
+                    .fg(current_timer_color)
+                    .bg(config.background()),
+            ),
+        )
+        .gauge_style(
+            Style::default()
+                .fg(config.palette().gauge.unwrap_or(current_timer_color))
+                .bg(config.background())
+                .add_modifier(Modifier::ITALIC),
+        )
+        .label(gauge_label)
+        .ratio(percentage_completed / 100.0)
+        .use_unicode(true);
+
+    let divided_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(2)])
+        .split(area);
+
+    f.render_widget(paragraph, divided_chunks[0]);
+    f.render_widget(timer_gauge, divided_chunks[1]);
+}
+
+pub fn setstab_rendering<B: Backend>(
     config: &mut Configuration,
     f: &mut Frame<B>,
     chunks: Vec<Rect>,
@@ -464,30 +1482,19 @@ pub fn setstab_rendering<B: Backend>(
     let selected_style = Style::default().add_modifier(Modifier::REVERSED);
     let header_cells = ["Sets"]
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(get_foreground_color(config.darkmode))));
+        .map(|h| Cell::from(*h).style(Style::default().fg(config.foreground())));
     let header = Row::new(header_cells)
-        .style(
-            Style::default().bg(AcceptedColors::from_str(&config.activecolor)
-                .unwrap()
-                .to_color()),
-        )
+        .style(Style::default().bg(config.active_color()))
         .height(1)
         .bottom_margin(1);
 
     let items = config.read_set_files().unwrap();
-    let rows = items.iter().map(|item| {
-        let height = //item
-            //.iter()
-            //map(|content| content.chars().filter(|c| *c == '\n').count())
-            //.max()
-            //.unwrap_or(0)
-            1;
-        let cells = item;//.iter().map(|c| Cell::from(&c[..]));
-        Row::new(vec![Cell::from(item.to_string())]).height(height as u16).bottom_margin(1)
-    });
+    let rows = items
+        .iter()
+        .map(|item| Row::new(vec![Cell::from(item.to_string())]).bottom_margin(1));
     let t: Table<'_> = Table::new(rows)
         .header(header)
-        .block(Block::default().borders(Borders::ALL))
+        .block(bordered_block(config))
         .highlight_style(selected_style)
         .highlight_symbol(">> ")
         .widths(&[
@@ -501,16 +1508,24 @@ pub fn setstab_rendering<B: Backend>(
         config.table_state_sets.select(Some(0))
     }
     f.render_stateful_widget(t, chunks[1], &mut config.table_state_sets);
+    config.table_rows = table_row_areas(
+        chunks[1],
+        config.table_state_sets.offset(),
+        &vec![1; items.len()],
+    );
     //* */
-    let text = "Press <ENTER> to save the configuration";
+    let text = format!(
+        "Press {} to load the selected set, {} to save the timers as a new set, {} to delete the set",
+        config.key_map.keys(Context::Sets, Action::ApplySet),
+        config.key_map.keys(Context::Sets, Action::SaveSet),
+        config.key_map.keys(Context::Sets, Action::Delete)
+    );
     let paragraph = Paragraph::new(Span::styled(
         text,
         Style::default()
             .add_modifier(Modifier::ITALIC)
-            .fg(AcceptedColors::from_str(&config.activecolor)
-                .unwrap()
-                .to_color())
-            .bg(get_background_color(config.darkmode)),
+            .fg(config.active_color())
+            .bg(config.background()),
     ))
     .alignment(Alignment::Center)
     .wrap(Wrap { trim: true });
@@ -525,69 +1540,44 @@ pub fn configtab_rendering<B: Backend>(
     let selected_style = Style::default().add_modifier(Modifier::REVERSED);
     let header_cells = ["Configuration", "Value"]
         .iter()
-        .map(|h| Cell::from(*h).style(Style::default().fg(get_foreground_color(config.darkmode))));
+        .map(|h| Cell::from(*h).style(Style::default().fg(config.foreground())));
     let header = Row::new(header_cells)
-        .style(
-            Style::default().bg(AcceptedColors::from_str(&config.activecolor)
-                .unwrap()
-                .to_color()),
-        )
+        .style(Style::default().bg(config.active_color()))
         .height(1)
         .bottom_margin(1);
     if config.table_state_config.selected().is_none() {
-        config.darkmode_str = config.darkmode.to_string();
-        config.activecolor_str = config.activecolor.clone();
-        config.reverseadding_str = config.reverseadding.to_string();
-        config.move_finished_timer_str = config.move_finished_timer.to_string();
-        config.action_timeout_str = config.action_timeout.clone();
-        config.pomodoro_time_table_str = config.pomodoro_time.to_string();
-        config.pomodoro_smallbreak_table_str = config.pomodoro_smallbreak.to_string();
-        config.pomodoro_bigbreak_table_str = config.pomodoro_bigbreak.to_string();
-    }
-    let items = vec![
-        vec!["Darkmode".to_string(), config.darkmode_str.to_owned()],
-        vec![
-            "Active Color".to_string(),
-            config.activecolor_str.to_owned(),
-        ],
-        vec![
-            "Reverse Adding of Timers".to_string(),
-            config.reverseadding_str.to_owned(),
-        ],
-        vec![
-            "Move Finished Timer to End".to_string(),
-            config.move_finished_timer_str.to_owned(),
-        ],
-        vec![
-            "Action After Timers Done".to_string(),
-            config.action_timeout_str.to_owned(),
-        ],
-        vec![
-            "Pomodoro Time".to_string(),
-            config.pomodoro_time_table_str.to_owned(),
-        ],
-        vec![
-            "Pomodoro Small Break Time".to_string(),
-            config.pomodoro_smallbreak_table_str.to_owned(),
-        ],
-        vec![
-            "Pomodoro Big Break Time".to_string(),
-            config.pomodoro_bigbreak_table_str.to_owned(),
-        ],
-    ];
-    let rows = items.iter().map(|item| {
-        let height = item
-            .iter()
-            .map(|content| content.chars().filter(|c| *c == '\n').count())
-            .max()
-            .unwrap_or(0)
-            + 1;
+        config.load_setting_values();
+    }
+    let items: Vec<Vec<String>> = SETTINGS
+        .iter()
+        .zip(&config.setting_values)
+        .enumerate()
+        .map(|(i, (setting, value))| {
+            let value = match (&config.setting_edit, &setting.kind) {
+                (Some(input), _) if i == config.selected_setting() => format!("{}_", input),
+                (_, SettingKind::Color { allow_default }) => color_value(value, *allow_default),
+                _ => value.clone(),
+            };
+            vec![setting.label.to_string(), value]
+        })
+        .collect();
+    let heights: Vec<u16> = items
+        .iter()
+        .map(|item| {
+            item.iter()
+                .map(|content| content.chars().filter(|c| *c == '\n').count())
+                .max()
+                .unwrap_or(0) as u16
+                + 1
+        })
+        .collect();
+    let rows = items.iter().zip(&heights).map(|(item, &height)| {
         let cells = item.iter().map(|c| Cell::from(&c[..]));
-        Row::new(cells).height(height as u16).bottom_margin(1)
+        Row::new(cells).height(height).bottom_margin(1)
     });
     let t: Table<'_> = Table::new(rows)
         .header(header)
-        .block(Block::default().borders(Borders::ALL))
+        .block(bordered_block(config))
         .highlight_style(selected_style)
         .highlight_symbol(">> ")
         .widths(&[
@@ -600,32 +1590,930 @@ pub fn configtab_rendering<B: Backend>(
     if config.table_state_config.selected().is_none() {
         config.table_state_config.select(Some(0))
     }
-    f.render_stateful_widget(t, chunks[1], &mut config.table_state_config);
+    let areas = if chunks[1].width < STACK_WIDTH {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(chunks[1])
+    } else {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)].as_ref())
+            .split(chunks[1])
+    };
+    f.render_stateful_widget(t, areas[0], &mut config.table_state_config);
+    config.table_rows = table_row_areas(areas[0], config.table_state_config.offset(), &heights);
+    timer_types_rendering(config, f, areas[1]);
+    if config.color_picker.is_some() {
+        color_picker_rendering(config, f, chunks[1]);
+    }
     //* */
-    let text = "Press <ENTER> to save the configuration";
+    let text = match &config.status_message {
+        Some(message) if !config.types_focus && config.color_picker.is_none() => message.clone(),
+        _ if config.setting_edit.is_some() => format!(
+            "Type {}, {} to keep the value, {} to cancel",
+            SETTINGS[config.selected_setting()].accepted_values(),
+            config.key_map.keys(Context::Input, Action::Run),
+            config.key_map.keys(Context::Input, Action::Cancel)
+        ),
+        _ => format!(
+            "Press {} to save the configuration, {} to type a value, {} to pick a color, {} to edit the timer types",
+            config.key_map.keys(Context::Config, Action::Save),
+            config.key_map.keys(Context::Config, Action::EditValue),
+            config.key_map.keys(Context::Config, Action::PickColor),
+            config.key_map.keys(Context::Config, Action::EditTypes)
+        ),
+    };
     let paragraph = Paragraph::new(Span::styled(
         text,
         Style::default()
             .add_modifier(Modifier::ITALIC)
-            .fg(AcceptedColors::from_str(&config.activecolor)
-                .unwrap()
-                .to_color())
-            .bg(get_background_color(config.darkmode)),
+            .fg(config.active_color())
+            .bg(config.background()),
     ))
     .alignment(Alignment::Center)
     .wrap(Wrap { trim: true });
     f.render_widget(paragraph, chunks[chunks.len() - 1]);
 }
 
+/// The 256-color palette in rows of 16 above the selected color, or the hex value being typed
+fn color_picker_rendering<B: Backend>(config: &Configuration, f: &mut Frame<B>, area: Rect) {
+    let Some(picker) = &config.color_picker else {
+        return;
+    };
+    let (width, height) = (52, 22);
+    let popup = Rect {
+        x: area.x + area.width.saturating_sub(width) / 2,
+        y: area.y + area.height.saturating_sub(height) / 2,
+        width: width.min(area.width),
+        height: height.min(area.height),
+    };
+    let mut lines: Vec<Line> = (0..16u16)
+        .map(|row| {
+            let spans = (0..16u16)
+                .flat_map(|column| {
+                    let index = (row * 16 + column) as u8;
+                    let color = AcceptedColors::from_index(index).to_color();
+                    let cell = if index == picker.index && picker.hex.is_empty() {
+                        let (r, g, b) = index_to_rgb(index);
+                        let bright = r as u32 * 299 + g as u32 * 587 + b as u32 * 114 > 128_000;
+                        let mark = if bright { Color::Black } else { Color::White };
+                        Span::styled("[]", Style::default().fg(mark).bg(color))
+                    } else {
+                        Span::styled("  ", Style::default().bg(color))
+                    };
+                    [cell, Span::raw(" ")]
+                })
+                .collect::<Vec<Span>>();
+            Line::from(spans)
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(if picker.hex.is_empty() {
+        let (r, g, b) = index_to_rgb(picker.index);
+        Line::from(format!(
+            "{} (#{:02x}{:02x}{:02x})",
+            AcceptedColors::from_index(picker.index),
+            r,
+            g,
+            b
+        ))
+    } else {
+        let swatch = AcceptedColors::parse(&picker.hex).map_or(Style::default(), |color| {
+            Style::default().bg(color.to_color())
+        });
+        Line::from(vec![
+            Span::raw(format!("Hex: {}_ ", picker.hex)),
+            Span::styled("    ", swatch),
+        ])
+    });
+    lines.push(Line::from(Span::styled(
+        config.status_message.clone().unwrap_or(format!(
+            "{} terminal, {} for a hex value, {} picks",
+            color_support(),
+            config.key_map.keys(Context::ColorPicker, Action::HexValue),
+            config.key_map.keys(Context::ColorPicker, Action::Pick)
+        )),
+        Style::default().add_modifier(Modifier::ITALIC),
+    )));
+
+    let title = match &picker.target {
+        ColorTarget::Setting(i) => format!(" {} ", SETTINGS[*i].label),
+        ColorTarget::TimerType(name) => format!(" Color of '{}' ", name),
+    };
+    let paragraph = Paragraph::new(lines)
+        .style(
+            Style::default()
+                .fg(config.foreground())
+                .bg(config.background()),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_type(BorderType::Thick)
+                .border_style(Style::default().fg(config.active_color()))
+                .title(title),
+        );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+/// A color setting for the Config tab, colors that can't be parsed are marked as invalid
+fn color_value(value: &str, allow_default: bool) -> String {
+    if value.is_empty()
+        || AcceptedColors::parse(value).is_some()
+        || (allow_default && value == "Default")
+    {
+        value.to_string()
+    } else {
+        format!("{} (invalid)", value)
+    }
+}
+
+/// The timer types with their colors next to the settings of the Config tab, a color that
+/// isn't one of the accepted colors is marked as invalid
+fn timer_types_rendering<B: Backend>(config: &mut Configuration, f: &mut Frame<B>, area: Rect) {
+    let active_color = config.active_color();
+    let foreground = config.foreground();
+    let mut rows: Vec<Row> = type_names(config)
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let color = config.timer_colors[&name].clone();
+            let valid = has_valid_color(config, &name);
+            let name = match &config.type_edit {
+                Some(TypeEdit::Rename { input, .. })
+                    if config.table_state_types.selected() == Some(i) =>
+                {
+                    format!("{}_", input)
+                }
+                _ => name.clone(),
+            };
+            let swatch = AcceptedColors::from_str(&color).unwrap().to_color();
+            let color_cell = if valid {
+                Cell::from(Line::from(vec![
+                    Span::styled("■ ", Style::default().fg(swatch)),
+                    Span::raw(color),
+                ]))
+            } else {
+                Cell::from(Line::from(vec![
+                    Span::styled("■ ", Style::default().fg(swatch)),
+                    Span::styled(
+                        format!("{} (invalid)", color),
+                        Style::default()
+                            .fg(config.palette().finished)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]))
+            };
+            Row::new(vec![Cell::from(name), color_cell])
+        })
+        .collect();
+    if let Some(TypeEdit::New(input)) = &config.type_edit {
+        rows.push(Row::new(vec![
+            Cell::from(format!("{}_", input)),
+            Cell::from("Green"),
+        ]));
+    }
+
+    let header = Row::new(["Type", "Color"].iter().map(|h| Cell::from(*h)))
+        .style(Style::default().fg(foreground).bg(active_color))
+        .bottom_margin(1);
+    let block = if config.types_focus {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().fg(active_color))
+    } else {
+        bordered_block(config)
+    }
+    .title(" Timer Types ");
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+        .split(area);
+    let highlight = if config.types_focus {
+        Style::default().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default()
+    };
+    let table = Table::new(rows)
+        .header(header)
+        .block(block)
+        .highlight_style(highlight)
+        .highlight_symbol(">> ")
+        .widths(&[Constraint::Percentage(40), Constraint::Percentage(60)]);
+    if config.table_state_types.selected().is_none() && !config.timer_colors.is_empty() {
+        config.table_state_types.select(Some(0));
+    }
+    // the row being added is the selected one while it is typed
+    let mut state = config.table_state_types.clone();
+    if matches!(config.type_edit, Some(TypeEdit::New(_))) {
+        state.select(Some(config.timer_colors.len()));
+    }
+    f.render_stateful_widget(table, layout[0], &mut state);
+    *config.table_state_types.offset_mut() = state.offset();
+
+    let keys = |context, action| config.key_map.keys(context, action);
+    let hint = match &config.status_message {
+        Some(message) if config.types_focus => message.clone(),
+        _ if config.type_edit.is_some() => format!(
+            "{} save, {} cancel",
+            keys(Context::Input, Action::Run),
+            keys(Context::Input, Action::Cancel)
+        ),
+        _ if config.types_focus => format!(
+            "{} add, {} rename, {} delete, {}/{} or {} color, {} back",
+            keys(Context::TimerTypes, Action::AddType),
+            keys(Context::TimerTypes, Action::Rename),
+            keys(Context::TimerTypes, Action::Delete),
+            keys(Context::TimerTypes, Action::PreviousValue),
+            keys(Context::TimerTypes, Action::NextValue),
+            keys(Context::TimerTypes, Action::PickColor),
+            keys(Context::TimerTypes, Action::LeaveTypes)
+        ),
+        _ => String::new(),
+    };
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            hint,
+            Style::default()
+                .add_modifier(Modifier::ITALIC)
+                .fg(foreground),
+        )),
+        layout[1],
+    );
+}
+
+pub fn statisticstab_rendering<B: Backend>(config: &Configuration, f: &mut Frame<B>, area: Rect) {
+    let (from, to) = config.stats_dates();
+    let stats = Statistics::from_history(&config.history.entries, from, to);
+    let active_color = config.active_color();
+    let style = Style::default()
+        .fg(config.foreground())
+        .bg(config.background());
+
+    let mut summary = vec![
+        Line::from(format!(
+            "Range: < {} >  {} - {}",
+            config.stats_range, stats.from, stats.to
+        )),
+        Line::from(format!(
+            "Focused: {}   Overtime: {}   Pomodoros completed: {} (interrupted: {})   Streak: {} days (longest {})",
+            format_duration(stats.total_focused_secs),
+            format_duration(stats.total_overtime_secs),
+            stats.pomodoros_completed,
+            stats.pomodoros_interrupted,
+            stats.current_streak,
+            stats.longest_streak
+        )),
+    ];
+    if let Some(error) = &config.history.error {
+        summary.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(summary.len() as u16 + 2),
+            Constraint::Percentage(50),
+            Constraint::Percentage(50),
+        ])
+        .split(area);
+    let direction = if area.width < STACK_WIDTH {
+        Direction::Vertical
+    } else {
+        Direction::Horizontal
+    };
+    let rows = [chunks[1], chunks[2]].map(|chunk| {
+        Layout::default()
+            .direction(direction.clone())
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunk)
+    });
+
+    let paragraph = Paragraph::new(summary)
+        .block(bordered_block(config).title("Statistics"))
+        .style(style);
+    f.render_widget(paragraph, chunks[0]);
+
+    // bar charts show minutes, labelled with the day, week or type
+    let day_format = if stats.focused_per_day.len() > 10 {
+        "%d"
+    } else {
+        "%m-%d"
+    };
+    let per_day: Vec<(String, u64)> = stats
+        .focused_per_day
+        .iter()
+        .map(|(day, secs)| (day.format(day_format).to_string(), secs / 60))
+        .collect();
+    let per_week: Vec<(String, u64)> = stats
+        .focused_per_week
+        .iter()
+        .map(|(week, secs)| (week.clone(), secs / 60))
+        .collect();
+    let group_key = config.key_map.keys(Context::Statistics, Action::GroupByTag);
+    let (per_group, group_title) = if config.stats_by_tag {
+        (
+            &stats.focused_per_tag,
+            format!("Focused minutes per tag ({}: per type)", group_key),
+        )
+    } else {
+        (
+            &stats.focused_per_type,
+            format!("Focused minutes per type ({}: per tag)", group_key),
+        )
+    };
+    let per_group: Vec<(String, u64)> = per_group
+        .iter()
+        .map(|(group, secs)| (group.clone(), secs / 60))
+        .collect();
+    let charts = [
+        (per_day, "Focused minutes per day", rows[0][0]),
+        (per_week, "Focused minutes per week", rows[0][1]),
+        (per_group, group_title.as_str(), rows[1][0]),
+    ];
+    for (data, title, chunk) in charts.iter() {
+        let data: Vec<(&str, u64)> = data.iter().map(|(l, v)| (l.as_str(), *v)).collect();
+        // fit all bars into the chart, but keep them wide enough for the labels
+        let bar_width = (chunk.width.saturating_sub(2) / data.len().max(1) as u16)
+            .saturating_sub(1)
+            .clamp(1, 9);
+        let chart = BarChart::default()
+            .block(bordered_block(config).title(*title))
+            .data(&data)
+            .bar_width(bar_width)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(active_color))
+            .value_style(Style::default().fg(config.background()).bg(active_color))
+            .style(style);
+        f.render_widget(chart, *chunk);
+    }
+
+    let sparkline_data: Vec<u64> = stats
+        .focused_per_day
+        .iter()
+        .map(|(_, secs)| secs / 60)
+        .collect();
+    let sparkline = Sparkline::default()
+        .block(bordered_block(config).title("Trend (minutes per day)"))
+        .data(&sparkline_data)
+        .style(style.fg(active_color));
+    f.render_widget(sparkline, rows[1][1]);
+}
+
+/// Color of a timer depending on its type and whether it is currently running
+fn timer_color(config: &Configuration, timer: &Timer) -> Color {
+    if timer.is_active {
+        running_color(config, timer)
+    } else {
+        config.palette().inactive
+    }
+}
+
+/// Color of a running timer: the color of its type, the active color or the paused color
+fn running_color(config: &Configuration, timer: &Timer) -> Color {
+    if let (true, Some(paused)) = (timer.paused, config.palette().paused) {
+        return paused;
+    }
+    match &timer.timer_type {
+        Some(timer_type) => {
+            // older timers store the color itself instead of the type
+            let color = config.timer_colors.get(timer_type).unwrap_or(timer_type);
+            AcceptedColors::from_str(color).unwrap().to_color()
+        }
+        None => config.active_color(),
+    }
+}
+
+/// The timer shown in the focus mode: the running timer of the left column, or of the right
+/// one if the left column is done
+fn focused_timer(config: &Configuration) -> Option<usize> {
+    config.running_timer_indices().first().copied()
+}
+
+/// Only the focused timer: its description, the remaining time in big digits, a gauge and
+/// what comes next in its column
+fn focus_rendering<B: Backend>(f: &mut Frame<B>, config: &Configuration, size: Rect) {
+    let keys = |context, action| config.key_map.keys(context, action);
+    let hints = Paragraph::new(Span::styled(
+        format!(
+            "{}: pause  {}: skip  {}: pause all  {}: leave  {}: quit",
+            keys(Context::Focus, Action::Pause),
+            keys(Context::Focus, Action::Skip),
+            keys(Context::Focus, Action::PauseAll),
+            keys(Context::Focus, Action::LeaveFocus),
+            keys(Context::Global, Action::Quit)
+        ),
+        Style::default()
+            .fg(config.palette().inactive)
+            .add_modifier(Modifier::ITALIC),
+    ))
+    .alignment(Alignment::Center);
+    f.render_widget(
+        hints,
+        Rect::new(size.x, size.y + size.height - 1, size.width, 1),
+    );
+
+    let Some(i) = focused_timer(config) else {
+        let paragraph = Paragraph::new("No timer is running").alignment(Alignment::Center);
+        f.render_widget(
+            paragraph,
+            Rect::new(size.x, size.y + size.height / 2, size.width, 1),
+        );
+        return;
+    };
+    let timer = &config.timers[i];
+    let width = size.width as usize;
+    let digits_height = (size.height / 2).max(2);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(digits_height),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(1),
+        ])
+        .split(size);
+
+    let color = if timer.awaiting_ack {
+        config.palette().finished
+    } else {
+        running_color(config, timer)
+    };
+    let mut description = timer.description_with_tags();
+    if timer.paused {
+        description = format!("{} [paused]", description);
+    }
+    let title = Paragraph::new(Span::styled(
+        truncate(&description, width),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    ))
+    .alignment(Alignment::Center);
+    f.render_widget(title, chunks[1]);
+
+    let secs = if timer.awaiting_ack {
+        timer.overtime_secs()
+    } else {
+        timer.timeleft_secs
+    };
+    let digits = big_digits::render(&countdown_text(secs), size.width * 4 / 5, digits_height);
+    let padding = (digits_height as usize).saturating_sub(digits.len()) / 2;
+    let lines: Vec<Line> = std::iter::repeat_n(String::new(), padding)
+        .chain(digits)
+        .map(Line::from)
+        .collect();
+    let paragraph = Paragraph::new(lines)
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(color));
+    f.render_widget(paragraph, chunks[3]);
+
+    if timer.awaiting_ack {
+        let text = Paragraph::new(Span::styled(
+            truncate(
+                &format!(
+                    "Time is up! Overtime +{} - press {} to continue",
+                    format_clock(secs),
+                    config.key_map.keys(Context::Focus, Action::Acknowledge)
+                ),
+                width,
+            ),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ))
+        .alignment(Alignment::Center);
+        f.render_widget(text, chunks[5]);
+    } else {
+        let percentage_completed = elapsed_percentage(timer);
+        let gauge = Gauge::default()
+            .gauge_style(
+                Style::default()
+                    .fg(config.palette().gauge.unwrap_or(color))
+                    .bg(config.background()),
+            )
+            .label(format!("{:.0}%", percentage_completed))
+            .ratio(percentage_completed / 100.0)
+            .use_unicode(true);
+        f.render_widget(gauge, centered_rect(60, 100, chunks[5]));
+    }
+
+    let next = config
+        .column_indices(timer.left_view)
+        .into_iter()
+        .find(|&j| j > i && config.timers[j].timeleft_secs > 0)
+        .map(|j| &config.timers[j]);
+    let next_text = match next {
+        Some(next) => format!(
+            "Next: {} ({})",
+            next.description,
+            format_clock(next.timeleft_secs)
+        ),
+        None => "Next: nothing, the column is done after this timer".to_string(),
+    };
+    let next_paragraph = Paragraph::new(Span::styled(
+        truncate(&next_text, width),
+        Style::default().fg(config.palette().inactive),
+    ))
+    .alignment(Alignment::Center);
+    f.render_widget(next_paragraph, chunks[7]);
+}
+
+/// Elapsed part of the timer in percent
+fn elapsed_percentage(timer: &Timer) -> f64 {
+    if timer.initial_time > 0 {
+        (timer.initial_time - timer.timeleft_secs.min(timer.initial_time)) as f64
+            / timer.initial_time as f64
+            * 100.0
+    } else {
+        100.0
+    }
+}
+
+/// Width of the text inside the borders of a block
+fn inner_width(area: Rect) -> usize {
+    area.width.saturating_sub(2) as usize
+}
+
+/// Text of a timer row, prefixed with a marker if it is part of a multi-selection
+fn timer_text(timer: &Timer) -> String {
+    if timer.marked {
+        format!("* {}", timer.formatted())
+    } else {
+        timer.formatted()
+    }
+}
+
+/// A finished timer that holds its column until it is acknowledged, drawn in reverse video
+/// with the time since it finished
+fn waiting_timer_rendering<B: Backend>(
+    f: &mut Frame<B>,
+    config: &Configuration,
+    timer: &Timer,
+    area: Rect,
+) {
+    let color = config.active_color();
+    let text = vec![
+        Line::from(truncate(&timer_text(timer), inner_width(area))),
+        Line::from(Span::styled(
+            truncate(
+                &format!(
+                    "Time is up! Overtime +{} - press {} or run 'next' to continue",
+                    format_clock(timer.overtime_secs()),
+                    config.key_map.keys(Context::Timer, Action::Acknowledge)
+                ),
+                inner_width(area),
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+    ];
+    let paragraph = Paragraph::new(text)
+        .block(timer_block(config, timer, Borders::ALL))
+        .style(Style::default().fg(config.background()).bg(color));
+    f.render_widget(paragraph, area);
+}
+
+/// Cycle counter of a Pomodoro timer, e.g. "Pomodoro 2/4" or "Long break"
+fn pomodoro_title(config: &Configuration, timer: &Timer) -> Option<String> {
+    let cycle = timer.pomodoro_cycle?;
+    let cycles = config.pomodoro_cycles.max(1);
+    Some(if timer.timer_type.as_deref() != Some("break") {
+        format!(" Pomodoro {}/{} ", cycle, cycles)
+    } else if cycle >= cycles {
+        " Long break ".to_string()
+    } else {
+        format!(" Break {}/{} ", cycle, cycles)
+    })
+}
+
+/// Border block of a timer, drawn thick and bold if the timer is selected
+fn timer_block<'a>(config: &Configuration, timer: &Timer, borders: Borders) -> Block<'a> {
+    let mut block = Block::default().borders(borders);
+    if borders.contains(Borders::TOP) {
+        if let Some(title) = pomodoro_title(config, timer) {
+            block = block.title(title);
+        }
+    }
+    if config.input_mode != InputMode::Command && config.selected_timer == Some(timer.id as usize) {
+        block
+            .border_type(BorderType::Thick)
+            .border_style(Style::default().add_modifier(Modifier::BOLD))
+    } else {
+        block
+    }
+}
+
+/// Block with all borders in the border color of the theme
+fn bordered_block<'a>(config: &Configuration) -> Block<'a> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(config.palette().border))
+}
+
 /// helper function to create a centered rect using up certain percentage of the available rect `r`
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let v_margin = r.height * (100 - percent_y) / 200;
-    let h_margin = r.width * (100 - percent_x) / 200;
+    // in u32, as the product overflows u16 on large terminals
+    let margin = |length: u16, percent: u16| {
+        (length as u32 * 100u32.saturating_sub(percent as u32) / 200) as u16
+    };
+    let v_margin = margin(r.height, percent_y);
+    let h_margin = margin(r.width, percent_x);
 
     Rect {
         x: r.x + h_margin,
         y: r.y + v_margin,
-        width: r.width - 2 * h_margin,
-        height: r.height - 2 * v_margin,
+        width: r.width.saturating_sub(2 * h_margin),
+        height: r.height.saturating_sub(2 * v_margin),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use ratatui::{backend::TestBackend, widgets::TableState, Terminal};
+
+    fn mouse(kind: MouseEventKind, x: u16, y: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column: x,
+            row: y,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn config_with_timers(count: usize) -> Configuration<'static> {
+        let mut config = Configuration::new();
+        for i in 0..count {
+            config
+                .timers
+                .push(Timer::new(format!("Timer {}", i), 60, true, None));
+        }
+        config.update_timers();
+        config.timer_areas = (0..count)
+            .map(|i| (i, Rect::new(0, 3 * i as u16, 20, 3)))
+            .collect();
+        config
+    }
+
+    #[test]
+    fn table_rows_match_the_rendered_table() {
+        // a scrolled table with a row of two lines, as the Config tab draws it
+        let heights = [1, 2, 1, 1, 1, 1, 1, 1];
+        let area = Rect::new(2, 1, 30, 14);
+        let mut state = TableState::default();
+        state.select(Some(6));
+        let mut terminal = Terminal::new(TestBackend::new(34, 16)).unwrap();
+        terminal
+            .draw(|f| {
+                let rows = heights.iter().enumerate().map(|(i, &height)| {
+                    Row::new(vec![Cell::from(format!("row{}", i))])
+                        .height(height)
+                        .bottom_margin(1)
+                });
+                let table = Table::new(rows)
+                    .header(Row::new(vec!["Header"]).height(1).bottom_margin(1))
+                    .block(Block::default().borders(Borders::ALL))
+                    .highlight_symbol(">> ")
+                    .widths(&[Constraint::Percentage(100)]);
+                f.render_stateful_widget(table, area, &mut state);
+            })
+            .unwrap();
+        let rows = table_row_areas(area, state.offset(), &heights);
+        assert!(state.offset() > 0);
+        assert_eq!(rows.first().map(|(i, _)| *i), Some(state.offset()));
+        assert_eq!(rows.last().map(|(i, _)| *i), Some(6));
+
+        let buffer = terminal.backend().buffer();
+        for (i, row) in &rows {
+            let line: String = (row.x..row.right())
+                .map(|x| buffer.get(x, row.y).symbol.clone())
+                .collect();
+            assert!(line.contains(&format!("row{}", i)), "{:?}", line);
+        }
+        assert_eq!(row_at(&rows, 5, rows[0].1.y), Some(rows[0].0));
+        // the margin below a row and the border belong to no row
+        assert_eq!(row_at(&rows, 5, rows[0].1.bottom()), None);
+        assert_eq!(row_at(&rows, area.x, rows[0].1.y), None);
+    }
+
+    #[test]
+    fn draws_every_tab_in_any_terminal_size() {
+        let mut config = config_with_timers(3);
+        config.titles = vec!["Timer [1]", "Sets [2]", "Config [3]", "Statistics [4]"];
+        let input_field = InputField::new();
+        for (width, height) in [(20, 5), (40, 12), (60, 24), (300, 100)] {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            // the Sets tab is left out, it reads the sets directory
+            for index in [0, 2, STATISTICS_TAB] {
+                config.index = index;
+                terminal.draw(|f| ui(f, &mut config, &input_field)).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn stacks_the_columns_in_narrow_terminals() {
+        let mut config = config_with_timers(2);
+        config.titles = vec!["Timer [1]", "Sets [2]", "Config [3]", "Statistics [4]"];
+        config.timers[1].left_view = false;
+        let input_field = InputField::new();
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+        terminal.draw(|f| ui(f, &mut config, &input_field)).unwrap();
+        let [left, right] = config.column_areas;
+        assert_eq!(left.x, right.x);
+        assert!(right.y >= left.bottom());
+        let (_, area) = config.timer_areas.iter().find(|(i, _)| *i == 1).unwrap();
+        assert!(contains(right, area.x, area.y));
+    }
+
+    #[test]
+    fn shows_only_the_running_timer_in_the_focus_mode() {
+        let mut config = config_with_timers(3);
+        config.titles = vec!["Timer [1]", "Sets [2]", "Config [3]", "Statistics [4]"];
+        let mut input_field = InputField::new();
+        let mut pause_flag = false;
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        handle_key_press(
+            key(KeyCode::Char('f')),
+            &mut config,
+            &mut input_field,
+            &mut pause_flag,
+        )
+        .unwrap();
+        assert!(config.focus_mode);
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+        terminal.draw(|f| ui(f, &mut config, &input_field)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol.clone())
+            .collect();
+        // the running timer and the one that comes next in its column
+        assert!(screen.contains("Timer 0"));
+        assert!(screen.contains("Timer 1"));
+        assert!(!screen.contains("Timer 2"));
+        assert_eq!(config.tabs_area, Rect::default());
+
+        handle_key_press(
+            key(KeyCode::Char(' ')),
+            &mut config,
+            &mut input_field,
+            &mut pause_flag,
+        )
+        .unwrap();
+        assert!(pause_flag);
+        handle_key_press(
+            key(KeyCode::Esc),
+            &mut config,
+            &mut input_field,
+            &mut pause_flag,
+        )
+        .unwrap();
+        assert!(!config.focus_mode);
+    }
+
+    #[test]
+    fn keeps_centered_rects_inside_large_terminals() {
+        // built by hand, Rect::new shrinks areas this large
+        let area = Rect {
+            x: 0,
+            y: 0,
+            width: 1000,
+            height: 700,
+        };
+        let centered = centered_rect(60, 20, area);
+        assert_eq!((centered.x, centered.y), (200, 280));
+        assert_eq!((centered.width, centered.height), (600, 140));
+        assert_eq!(centered_rect(120, 20, area).width, area.width);
+    }
+
+    #[test]
+    fn drags_a_timer_by_its_id() {
+        let mut config = config_with_timers(3);
+        let mut input_field = InputField::new();
+        let mut pause_flag = false;
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 5, 4);
+        handle_mouse_event(down, &mut config, &mut input_field, &mut pause_flag).unwrap();
+        assert_eq!(config.selected_timer, Some(1));
+        assert_eq!(config.dragged_timer, Some(1));
+
+        // a timer removed in front of the dragged one renumbers it, the drag ends
+        config.timers.remove(0);
+        config.update_timers();
+        assert_eq!(config.dragged_timer, None);
+    }
+
+    #[test]
+    fn keeps_the_queue_order_while_the_table_view_is_sorted() {
+        let mut config = config_with_timers(3);
+        config.table_view = true;
+        config.table_sort = SortColumn::Description;
+        config.table_sort_descending = true;
+        let mut input_field = InputField::new();
+        let mut pause_flag = false;
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 5, 1);
+        handle_mouse_event(down, &mut config, &mut input_field, &mut pause_flag).unwrap();
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), 5, 7);
+        handle_mouse_event(up, &mut config, &mut input_field, &mut pause_flag).unwrap();
+        assert_eq!(config.status_message, Some(sorted_view_message(&config)));
+        let descriptions: Vec<&str> = config.timers.iter().map(|t| &t.description[..]).collect();
+        assert_eq!(descriptions, ["Timer 0", "Timer 1", "Timer 2"]);
+    }
+
+    #[test]
+    fn drops_nothing_when_the_dragged_timer_is_gone() {
+        let mut config = config_with_timers(3);
+        let mut input_field = InputField::new();
+        let mut pause_flag = false;
+        let down = mouse(MouseEventKind::Down(MouseButton::Left), 5, 7);
+        handle_mouse_event(down, &mut config, &mut input_field, &mut pause_flag).unwrap();
+        assert_eq!(config.dragged_timer, Some(2));
+
+        // the areas still show the removed timer until the next frame is drawn
+        config.timers.pop();
+        config.update_timers();
+        let up = mouse(MouseEventKind::Up(MouseButton::Left), 5, 1);
+        handle_mouse_event(up, &mut config, &mut input_field, &mut pause_flag).unwrap();
+        assert_eq!(config.dragged_timer, None);
+        let descriptions: Vec<&str> = config.timers.iter().map(|t| &t.description[..]).collect();
+        assert_eq!(descriptions, ["Timer 0", "Timer 1"]);
+        assert_eq!(timer_at(&config, 5, 7), None);
+    }
+
+    #[test]
+    fn fits_the_timers_with_room_for_the_scroll_lines() {
+        let heights = [3, 3, 3, 3, 3];
+        // a line is kept free below for "more below"
+        assert_eq!(visible_end(&heights, 0, 10), 3);
+        // and above once the list is scrolled
+        assert_eq!(visible_end(&heights, 1, 10), 3);
+        // the last timer needs no line below it
+        assert_eq!(visible_end(&heights[..2], 0, 6), 2);
+        assert_eq!(visible_end(&heights, 0, 2), 0);
+    }
+
+    #[test]
+    fn scrolls_just_far_enough_to_show_the_timer() {
+        let heights = [3, 3, 3, 3, 3];
+        assert_eq!(scroll_into_view(&heights, 0, 10, 2), 0);
+        assert_eq!(scroll_into_view(&heights, 0, 10, 4), 2);
+        assert_eq!(scroll_into_view(&heights, 3, 10, 1), 1);
+        // a column too short for any timer still starts at the selected one
+        assert_eq!(scroll_into_view(&heights, 0, 2, 3), 3);
+    }
+
+    #[test]
+    fn pages_through_the_column_of_the_selected_timer() {
+        let mut config = config_with_timers(6);
+        config.timers[1].left_view = false;
+        config.column_page_sizes = [2, 1];
+        let mut input_field = InputField::new();
+        let mut pause_flag = false;
+        let mut press = |config: &mut Configuration, code| {
+            let key = KeyEvent::new(code, KeyModifiers::NONE);
+            handle_key_press(key, config, &mut input_field, &mut pause_flag).unwrap();
+        };
+
+        config.selected_timer = Some(0);
+        press(&mut config, KeyCode::PageDown);
+        assert_eq!(config.selected_timer, Some(3));
+        press(&mut config, KeyCode::PageDown);
+        assert_eq!(config.selected_timer, Some(5));
+        press(&mut config, KeyCode::PageDown);
+        assert_eq!(config.selected_timer, Some(5));
+        press(&mut config, KeyCode::PageUp);
+        assert_eq!(config.selected_timer, Some(3));
+
+        // the right column holds a single timer
+        config.selected_timer = Some(1);
+        press(&mut config, KeyCode::PageDown);
+        assert_eq!(config.selected_timer, Some(1));
+    }
+
+    #[test]
+    fn drops_a_timer_into_the_other_column() {
+        let mut config = config_with_timers(3);
+        config.drop_timer(0, Some(2), false);
+        let order: Vec<(&str, bool)> = config
+            .timers
+            .iter()
+            .map(|t| (&t.description[..], t.left_view))
+            .collect();
+        assert_eq!(
+            order,
+            [("Timer 1", true), ("Timer 0", false), ("Timer 2", true)]
+        );
+        assert_eq!(config.selected_timer, Some(1));
     }
 }
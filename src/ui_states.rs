@@ -4,68 +4,65 @@ pub enum UiState {
     TimerUi,
     SetsUi,
     ConfigUi,
+    StatisticsUi,
 }
 
+/// Index of the Statistics tab in the tab titles
+pub const STATISTICS_TAB: usize = 3;
+
 impl UiState {
     pub fn get_current_ui(index: usize) -> Self {
         match index {
             0 => UiState::TimerUi,
             1 => UiState::SetsUi,
             2 => UiState::ConfigUi,
+            STATISTICS_TAB => UiState::StatisticsUi,
             _ => UiState::TimerUi,
         }
     }
 }
 
-pub enum ConfigType {
-    DarkMode,
-    ActiveColor,
-    ReverseAddingTimer,
-    MoveFinishedTimer,
-    ActionAfterTimer,
-    PomodoroTime,
-    PomodoroSmallBreak,
-    PomodoroBigBreak,
+/// Whether key presses in the Timer tab navigate the timers or go to the input line, either
+/// as a command or as a search
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputMode {
+    #[default]
+    Normal,
+    Command,
+    Search,
 }
 
-impl Default for ConfigType {
-    fn default() -> Self {
-        Self::DarkMode
-    }
+/// The color the color picker of the Config tab changes
+pub enum ColorTarget {
+    /// The index of a color setting in `SETTINGS`
+    Setting(usize),
+    TimerType(String),
 }
 
-impl ConfigType {
-    pub fn next(&mut self) {
-        *self = match self {
-            ConfigType::DarkMode => ConfigType::ActiveColor,
-            ConfigType::ActiveColor => ConfigType::ReverseAddingTimer,
-            ConfigType::ReverseAddingTimer => ConfigType::MoveFinishedTimer,
-            ConfigType::MoveFinishedTimer => ConfigType::ActionAfterTimer,
-            ConfigType::ActionAfterTimer => ConfigType::PomodoroTime,
-            ConfigType::PomodoroTime => ConfigType::PomodoroSmallBreak,
-            ConfigType::PomodoroSmallBreak => ConfigType::PomodoroBigBreak,
-            ConfigType::PomodoroBigBreak => ConfigType::DarkMode,
-        };
-    }
+/// The color picker of the Config tab, a grid of the 256-color palette. A hex value can be
+/// typed instead, it is used if it isn't empty.
+pub struct ColorPicker {
+    pub target: ColorTarget,
+    pub index: u8,
+    pub hex: String,
+}
 
-    pub fn previous(&mut self) {
-        *self = match self {
-            ConfigType::DarkMode => ConfigType::PomodoroBigBreak,
-            ConfigType::ActiveColor => ConfigType::DarkMode,
-            ConfigType::ReverseAddingTimer => ConfigType::ActiveColor,
-            ConfigType::MoveFinishedTimer => ConfigType::ReverseAddingTimer,
-            ConfigType::ActionAfterTimer => ConfigType::MoveFinishedTimer,
-            ConfigType::PomodoroTime => ConfigType::ActionAfterTimer,
-            ConfigType::PomodoroSmallBreak => ConfigType::PomodoroTime,
-            ConfigType::PomodoroBigBreak => ConfigType::PomodoroSmallBreak,
+/// A timer type name being typed in the Timer Types table of the Config tab
+pub enum TypeEdit {
+    New(String),
+    Rename { name: String, input: String },
+}
+
+impl TypeEdit {
+    pub fn input(&self) -> &str {
+        match self {
+            TypeEdit::New(input) | TypeEdit::Rename { input, .. } => input,
         }
     }
-}
 
-impl fmt::Display for ConfigType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    pub fn input_mut(&mut self) -> &mut String {
         match self {
-            _ => write!(f, ""),
+            TypeEdit::New(input) | TypeEdit::Rename { input, .. } => input,
         }
     }
 }
@@ -76,24 +73,6 @@ pub enum TimerAction {
     Shutdown,
 }
 
-impl TimerAction {
-    pub fn next(&self) -> Self {
-        match self {
-            TimerAction::None => TimerAction::Hibernate,
-            TimerAction::Hibernate => TimerAction::Shutdown,
-            TimerAction::Shutdown => TimerAction::None,
-        }
-    }
-
-    pub fn previous(&mut self) -> Self {
-        match self {
-            TimerAction::None => TimerAction::Shutdown,
-            TimerAction::Hibernate => TimerAction::None,
-            TimerAction::Shutdown => TimerAction::Hibernate,
-        }
-    }
-}
-
 impl fmt::Display for TimerAction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -116,3 +95,114 @@ impl FromStr for TimerAction {
         }
     }
 }
+
+/// What happens to a linked Taskwarrior task when its timer finishes
+pub enum TaskwarriorFinish {
+    None,
+    Done,
+    Annotate,
+}
+
+impl fmt::Display for TaskwarriorFinish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskwarriorFinish::None => write!(f, "None"),
+            TaskwarriorFinish::Done => write!(f, "Done"),
+            TaskwarriorFinish::Annotate => write!(f, "Annotate"),
+        }
+    }
+}
+
+impl FromStr for TaskwarriorFinish {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Done" => Ok(TaskwarriorFinish::Done),
+            "Annotate" => Ok(TaskwarriorFinish::Annotate),
+            _ => Ok(TaskwarriorFinish::None),
+        }
+    }
+}
+
+/// Date range shown in the Statistics tab
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum StatsRange {
+    #[default]
+    Today,
+    ThisWeek,
+    Last30Days,
+    Custom,
+}
+
+impl StatsRange {
+    pub fn next(&self) -> Self {
+        match self {
+            StatsRange::Today => StatsRange::ThisWeek,
+            StatsRange::ThisWeek => StatsRange::Last30Days,
+            StatsRange::Last30Days => StatsRange::Custom,
+            StatsRange::Custom => StatsRange::Today,
+        }
+    }
+
+    pub fn previous(&self) -> Self {
+        match self {
+            StatsRange::Today => StatsRange::Custom,
+            StatsRange::ThisWeek => StatsRange::Today,
+            StatsRange::Last30Days => StatsRange::ThisWeek,
+            StatsRange::Custom => StatsRange::Last30Days,
+        }
+    }
+}
+
+impl fmt::Display for StatsRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsRange::Today => write!(f, "Today"),
+            StatsRange::ThisWeek => write!(f, "This week"),
+            StatsRange::Last30Days => write!(f, "Last 30 days"),
+            StatsRange::Custom => write!(f, "Custom"),
+        }
+    }
+}
+
+/// Column the timers of the table view are sorted by, `Queue` keeps the order of the queue
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    #[default]
+    Queue,
+    Id,
+    Remaining,
+    EndTime,
+    Type,
+    Description,
+    Repeats,
+}
+
+impl SortColumn {
+    pub fn next(&self) -> Self {
+        match self {
+            SortColumn::Queue => SortColumn::Id,
+            SortColumn::Id => SortColumn::Remaining,
+            SortColumn::Remaining => SortColumn::EndTime,
+            SortColumn::EndTime => SortColumn::Type,
+            SortColumn::Type => SortColumn::Description,
+            SortColumn::Description => SortColumn::Repeats,
+            SortColumn::Repeats => SortColumn::Queue,
+        }
+    }
+}
+
+impl fmt::Display for SortColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortColumn::Queue => write!(f, "Queue"),
+            SortColumn::Id => write!(f, "Id"),
+            SortColumn::Remaining => write!(f, "Remaining"),
+            SortColumn::EndTime => write!(f, "End"),
+            SortColumn::Type => write!(f, "Type"),
+            SortColumn::Description => write!(f, "Description"),
+            SortColumn::Repeats => write!(f, "Repeats"),
+        }
+    }
+}
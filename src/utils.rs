@@ -1,10 +1,40 @@
 use std::collections::HashMap;
 
+use crate::color::AcceptedColors;
+
 pub fn reverse_bool(input: &str) -> String {
     let value = input.parse::<bool>().unwrap_or(false);
     (!value).to_string()
 }
 
+/// Shortens the text to the given number of characters, ending it with an ellipsis if it
+/// was cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let mut truncated: String = text.chars().take(width - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Moves a background or foreground color to the next or previous color, "Default" (the
+/// color of the darkmode setting) comes before Black and after White
+pub fn cycle_custom_color(input: &str, forward: bool) -> String {
+    match (AcceptedColors::parse(input), forward) {
+        (None, true) => AcceptedColors::Black.to_string(),
+        (None, false) => AcceptedColors::White.to_string(),
+        (Some(AcceptedColors::White), true) | (Some(AcceptedColors::Black), false) => {
+            "Default".to_string()
+        }
+        (Some(color), true) => color.next_color().to_string(),
+        (Some(color), false) => color.previous_color().to_string(),
+    }
+}
+
 /// Default timer types and their colors
 pub fn get_optional_timer_colors() -> HashMap<String, String> {
     HashMap::from([
@@ -19,3 +49,17 @@ pub fn get_optional_timer_colors() -> HashMap<String, String> {
         ("coding".to_string(), "LightGreen".to_string()),
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncates_with_an_ellipsis() {
+        assert_eq!(truncate("Write report", 20), "Write report");
+        assert_eq!(truncate("Write report", 12), "Write report");
+        assert_eq!(truncate("Write report", 6), "Write…");
+        assert_eq!(truncate("Wörter", 3), "Wö…");
+        assert_eq!(truncate("Write", 0), "");
+    }
+}